@@ -0,0 +1,655 @@
+use super::*;
+use frame_counter::{FrameClock, FrameCounter};
+use resampler::Resampler;
+use ring_buffer::RingBuffer;
+
+fn enabled_apu() -> APU {
+    let mut apu = APU::new();
+    apu.pulse1.set_enabled(true);
+    apu.pulse2.set_enabled(true);
+    apu
+}
+
+#[test]
+fn test_pulse_length_counter_load() {
+    let mut apu = APU::new();
+    //disabled channels ignore length loads
+    apu.write_register(0x4003, 0b0000_1000);
+    assert_eq!(apu.pulse1.length_counter(), 0);
+
+    let mut apu = enabled_apu();
+    apu.write_register(0x4003, 0b0000_1000);
+    assert_eq!(apu.pulse1.length_counter(), 254);
+    apu.clock_half_frame();
+    assert_eq!(apu.pulse1.length_counter(), 253);
+    //halt flag
+    apu.write_register(0x4000, 0b0010_0000);
+    apu.clock_half_frame();
+    assert_eq!(apu.pulse1.length_counter(), 253);
+    //disabling clears the counter
+    apu.pulse1.set_enabled(false);
+    assert_eq!(apu.pulse1.length_counter(), 0);
+}
+
+#[test]
+fn test_pulse_duty_sequence() {
+    let mut apu = enabled_apu();
+    //duty 2 (50%), constant volume 15
+    apu.write_register(0x4000, 0b1011_1111);
+    apu.write_register(0x4002, 0x10);
+    apu.write_register(0x4003, 0b0000_1000);
+    let mut high = 0;
+    for _ in 0..8 {
+        if apu.pulse1.output() == 15 {
+            high += 1;
+        }
+        for _ in 0..=0x10 {
+            apu.pulse1.clock_timer();
+        }
+    }
+    assert_eq!(high, 4);
+
+    //duty 0 (12.5%)
+    apu.write_register(0x4000, 0b0011_1111);
+    apu.write_register(0x4003, 0b0000_1000);
+    let mut high = 0;
+    for _ in 0..8 {
+        if apu.pulse1.output() == 15 {
+            high += 1;
+        }
+        for _ in 0..=0x10 {
+            apu.pulse1.clock_timer();
+        }
+    }
+    assert_eq!(high, 1);
+}
+
+#[test]
+fn test_pulse_timer_runs_at_half_cpu_rate() {
+    let mut apu = enabled_apu();
+    apu.write_register(0x4000, 0b1011_1111);
+    apu.write_register(0x4002, 0x10);
+    apu.write_register(0x4003, 0b0000_1000);
+    //sequence step 0 of duty 2 is low, the next step (7) is high
+    assert_eq!(apu.pulse1.output(), 0);
+    for _ in 0..2 {
+        apu.tick();
+    }
+    assert_eq!(apu.pulse1.output(), 15);
+}
+
+#[test]
+fn test_envelope_decay() {
+    let mut apu = enabled_apu();
+    //duty 3, envelope period 1
+    apu.write_register(0x4000, 0b1100_0001);
+    apu.write_register(0x4002, 0x10);
+    apu.write_register(0x4003, 0b0000_1000);
+    apu.clock_quarter_frame();
+    assert_eq!(apu.pulse1.output(), 15);
+    apu.clock_quarter_frame();
+    assert_eq!(apu.pulse1.output(), 15);
+    apu.clock_quarter_frame();
+    assert_eq!(apu.pulse1.output(), 14);
+    for _ in 0..40 {
+        apu.clock_quarter_frame();
+    }
+    assert_eq!(apu.pulse1.output(), 0);
+
+    //loop flag restarts the decay at 15
+    apu.write_register(0x4000, 0b1110_0000);
+    apu.write_register(0x4003, 0b0000_1000);
+    for _ in 0..16 {
+        apu.clock_quarter_frame();
+    }
+    assert_eq!(apu.pulse1.output(), 0);
+    apu.clock_quarter_frame();
+    assert_eq!(apu.pulse1.output(), 15);
+}
+
+#[test]
+fn test_sweep_negate_differs_between_channels() {
+    let mut apu = enabled_apu();
+    //enabled, period 0, negate, shift 1
+    apu.write_register(0x4001, 0b1000_1001);
+    apu.write_register(0x4002, 0x00);
+    apu.write_register(0x4003, 0b0000_1001);
+    apu.write_register(0x4005, 0b1000_1001);
+    apu.write_register(0x4006, 0x00);
+    apu.write_register(0x4007, 0b0000_1001);
+    apu.clock_half_frame();
+    assert_eq!(apu.pulse1.timer_period(), 0x7F);
+    assert_eq!(apu.pulse2.timer_period(), 0x80);
+}
+
+#[test]
+fn test_sweep_increase() {
+    let mut apu = enabled_apu();
+    //enabled, period 1, shift 2
+    apu.write_register(0x4001, 0b1001_0010);
+    apu.write_register(0x4002, 0x00);
+    apu.write_register(0x4003, 0b0000_1001);
+    apu.clock_half_frame();
+    assert_eq!(apu.pulse1.timer_period(), 0x140);
+    apu.clock_half_frame();
+    assert_eq!(apu.pulse1.timer_period(), 0x140);
+    apu.clock_half_frame();
+    assert_eq!(apu.pulse1.timer_period(), 0x190);
+}
+
+#[test]
+fn test_sweep_muting() {
+    let mut apu = enabled_apu();
+    apu.write_register(0x4000, 0b1111_1111);
+    //period below 8
+    apu.write_register(0x4002, 0x07);
+    apu.write_register(0x4003, 0b0000_1000);
+    assert!(apu.pulse1.is_muted());
+    //target overflow mutes even with the sweep disabled
+    apu.write_register(0x4001, 0b0000_0000);
+    apu.write_register(0x4002, 0xFF);
+    apu.write_register(0x4003, 0b0000_1111);
+    assert!(apu.pulse1.is_muted());
+    for _ in 0..16 {
+        assert_eq!(apu.pulse1.output(), 0);
+        apu.pulse1.clock_timer();
+    }
+    //a muted channel is never updated by the sweep
+    apu.write_register(0x4001, 0b1000_0001);
+    apu.clock_half_frame();
+    assert_eq!(apu.pulse1.timer_period(), 0x7FF);
+    apu.write_register(0x4001, 0b0000_0001);
+    apu.write_register(0x4003, 0b0000_0011);
+    assert!(!apu.pulse1.is_muted());
+}
+
+#[test]
+fn test_triangle_linear_counter() {
+    let mut apu = APU::new();
+    apu.triangle.set_enabled(true);
+    //control clear, reload value 3
+    apu.write_register(0x4008, 0b0000_0011);
+    apu.write_register(0x400A, 0x10);
+    apu.write_register(0x400B, 0b0000_1000);
+    apu.clock_quarter_frame();
+    assert_eq!(apu.triangle.linear_counter(), 3);
+    apu.clock_quarter_frame();
+    assert_eq!(apu.triangle.linear_counter(), 2);
+    apu.clock_quarter_frame();
+    apu.clock_quarter_frame();
+    apu.clock_quarter_frame();
+    assert_eq!(apu.triangle.linear_counter(), 0);
+
+    //control set keeps reloading
+    apu.write_register(0x4008, 0b1000_0011);
+    apu.write_register(0x400B, 0b0000_1000);
+    for _ in 0..5 {
+        apu.clock_quarter_frame();
+        assert_eq!(apu.triangle.linear_counter(), 3);
+    }
+}
+
+#[test]
+fn test_triangle_sequence() {
+    let mut apu = APU::new();
+    apu.triangle.set_enabled(true);
+    apu.write_register(0x4008, 0b1111_1111);
+    apu.write_register(0x400A, 0x02);
+    apu.write_register(0x400B, 0b0000_1000);
+    apu.clock_quarter_frame();
+    let mut steps = Vec::new();
+    for _ in 0..32 {
+        steps.push(apu.triangle.output());
+        for _ in 0..3 {
+            apu.tick();
+        }
+    }
+    assert_eq!(steps[0], 15);
+    assert_eq!(steps[15], 0);
+    assert_eq!(steps[16], 0);
+    assert_eq!(steps[31], 15);
+    assert_eq!(apu.triangle.output(), 15);
+
+    //a cleared linear counter holds the sequencer
+    apu.write_register(0x4008, 0b0000_0000);
+    apu.write_register(0x400B, 0b0000_1000);
+    apu.clock_quarter_frame();
+    apu.clock_quarter_frame();
+    let held = apu.triangle.output();
+    for _ in 0..30 {
+        apu.tick();
+    }
+    assert_eq!(apu.triangle.output(), held);
+}
+
+#[test]
+fn test_triangle_ultrasonic_silencing() {
+    let mut apu = APU::new();
+    apu.triangle.set_enabled(true);
+    apu.write_register(0x4008, 0b1111_1111);
+    apu.write_register(0x400A, 0x00);
+    apu.write_register(0x400B, 0b0000_1000);
+    apu.clock_quarter_frame();
+    for _ in 0..10 {
+        apu.tick();
+    }
+    assert_eq!(apu.triangle.output(), 15);
+
+    apu.triangle.silence_ultrasonic = false;
+    for _ in 0..10 {
+        apu.tick();
+    }
+    assert_ne!(apu.triangle.output(), 15);
+}
+
+#[test]
+fn test_noise_shift_register() {
+    let mut apu = APU::new();
+    apu.noise.set_enabled(true);
+    //long mode, period index 0
+    apu.write_register(0x400E, 0x00);
+    apu.noise.clock_timer();
+    //bit 0 of 1 xor bit 1 of 1 feeds a 1 into bit 14
+    assert_eq!(apu.noise.shift_register(), 0b0100_0000_0000_0000);
+
+    //long mode repeats after 32767 steps, short mode after 93
+    let mut apu = APU::new();
+    apu.write_register(0x400E, 0x00);
+    let mut steps = 0;
+    loop {
+        apu.noise.clock_timer();
+        apu.noise.clock_timer();
+        steps += 1;
+        if apu.noise.shift_register() == 1 {
+            break;
+        }
+    }
+    assert_eq!(steps, 32767);
+
+    let mut apu = APU::new();
+    apu.write_register(0x400E, 0b1000_0000);
+    //the starting state isn't part of the short loop, so wait until it repeats
+    for _ in 0..200 {
+        apu.noise.clock_timer();
+        apu.noise.clock_timer();
+    }
+    let start = apu.noise.shift_register();
+    let mut steps = 0;
+    loop {
+        apu.noise.clock_timer();
+        apu.noise.clock_timer();
+        steps += 1;
+        if apu.noise.shift_register() == start {
+            break;
+        }
+    }
+    assert_eq!(steps, 93);
+}
+
+#[test]
+fn test_noise_period_tables() {
+    //period index 15 is 4068 CPU cycles on NTSC and 3778 on PAL
+    for (region, period) in [(Region::Ntsc, 4068), (Region::Pal, 3778)] {
+        let mut apu = APU::with_region(region);
+        apu.write_register(0x400E, 0x0F);
+        let mut cycles = 0;
+        let start = apu.noise.shift_register();
+        while apu.noise.shift_register() == start {
+            apu.tick();
+            cycles += 1;
+        }
+        let start = apu.noise.shift_register();
+        let mut period_cycles = 0;
+        while apu.noise.shift_register() == start {
+            apu.tick();
+            period_cycles += 1;
+        }
+        assert!(cycles > 0);
+        assert_eq!(period_cycles, period);
+    }
+}
+
+#[test]
+fn test_noise_output_gated_by_length_counter() {
+    let mut apu = APU::new();
+    apu.noise.set_enabled(true);
+    apu.write_register(0x400C, 0b0001_1111);
+    apu.write_register(0x400E, 0x00);
+    assert_eq!(apu.noise.output(), 0);
+    apu.write_register(0x400F, 0b0000_1000);
+    apu.noise.clock_timer();
+    assert_eq!(apu.noise.output(), 15);
+}
+
+#[test]
+fn test_dmc_direct_load_and_output_unit() {
+    let mut apu = APU::new();
+    apu.write_register(0x4011, 0xFF);
+    assert_eq!(apu.dmc.output(), 0x7F);
+    apu.write_register(0x4011, 0x40);
+
+    //fastest rate, single byte sample at $C000
+    apu.write_register(0x4010, 0x0F);
+    apu.write_register(0x4012, 0x00);
+    apu.write_register(0x4013, 0x00);
+    apu.dmc.set_enabled(true);
+    assert_eq!(apu.dmc.sample_request(), Some(0xC000));
+    apu.dmc.fill_sample_buffer(0b0000_1111);
+    assert_eq!(apu.dmc.sample_request(), None);
+    assert!(!apu.dmc.is_active());
+
+    //the output unit picks up the buffer once the current (silent) byte ends
+    let mut levels = Vec::new();
+    for _ in 0..(54 * 16) {
+        apu.tick();
+        if levels.last() != Some(&apu.dmc.output()) {
+            levels.push(apu.dmc.output());
+        }
+    }
+    assert_eq!(
+        levels,
+        vec![0x40, 0x42, 0x44, 0x46, 0x48, 0x46, 0x44, 0x42, 0x40]
+    );
+}
+
+#[test]
+fn test_dmc_irq_and_loop() {
+    let mut apu = APU::new();
+    //irq enabled, two byte sample at $C040
+    apu.write_register(0x4010, 0b1000_0000);
+    apu.write_register(0x4012, 0x01);
+    apu.write_register(0x4013, 0x00);
+    apu.dmc.set_enabled(true);
+    assert_eq!(apu.dmc.sample_request(), Some(0xC040));
+    apu.dmc.fill_sample_buffer(0);
+    assert!(apu.irq());
+    //disabling the irq acknowledges it
+    apu.write_register(0x4010, 0b0000_0000);
+    assert!(!apu.irq());
+
+    //looping samples restart instead of raising the irq
+    apu.write_register(0x4010, 0b1100_0000);
+    apu.write_register(0x4013, 0x01);
+    apu.dmc.set_enabled(true);
+    assert_eq!(apu.dmc.bytes_remaining(), 17);
+    for _ in 0..17 {
+        apu.dmc.fill_sample_buffer(0);
+    }
+    assert!(!apu.irq());
+    assert_eq!(apu.dmc.bytes_remaining(), 17);
+    assert_eq!(apu.dmc.sample_request(), None);
+
+    apu.dmc.set_enabled(false);
+    assert!(!apu.dmc.is_active());
+}
+
+#[test]
+fn test_dmc_address_wraps_to_8000() {
+    let mut apu = APU::new();
+    apu.write_register(0x4012, 0xFF);
+    apu.write_register(0x4013, 0x04);
+    apu.dmc.set_enabled(true);
+    assert_eq!(apu.dmc.sample_request(), Some(0xFFC0));
+    for _ in 0..0x40 {
+        apu.dmc.fill_sample_buffer(0);
+        //let the output unit drain the buffer
+        for _ in 0..(428 * 8) {
+            apu.tick();
+        }
+    }
+    assert_eq!(apu.dmc.sample_request(), Some(0x8000));
+}
+
+fn frame_clocks(counter: &mut FrameCounter, cycles: u32) -> Vec<(u32, FrameClock)> {
+    let mut clocks = Vec::new();
+    for cycle in 1..=cycles {
+        let clock = counter.tick();
+        if clock != FrameClock::None {
+            clocks.push((cycle, clock));
+        }
+    }
+    clocks
+}
+
+#[test]
+fn test_frame_counter_four_step_sequence() {
+    let mut counter = FrameCounter::new(Region::Ntsc);
+    let clocks = frame_clocks(&mut counter, 29830 * 2);
+    assert_eq!(
+        clocks,
+        vec![
+            (7457, FrameClock::Quarter),
+            (14913, FrameClock::Half),
+            (22371, FrameClock::Quarter),
+            (29829, FrameClock::Half),
+            (29830 + 7457, FrameClock::Quarter),
+            (29830 + 14913, FrameClock::Half),
+            (29830 + 22371, FrameClock::Quarter),
+            (29830 + 29829, FrameClock::Half),
+        ]
+    );
+}
+
+#[test]
+fn test_frame_counter_five_step_sequence() {
+    let mut counter = FrameCounter::new(Region::Ntsc);
+    counter.write(0b1000_0000, false);
+    //the delayed reset clocks both units right away in 5-step mode
+    let clocks = frame_clocks(&mut counter, 4 + 37282);
+    assert_eq!(
+        clocks,
+        vec![
+            (4, FrameClock::Half),
+            (4 + 7457, FrameClock::Quarter),
+            (4 + 14913, FrameClock::Half),
+            (4 + 22371, FrameClock::Quarter),
+            (4 + 37281, FrameClock::Half),
+        ]
+    );
+    assert!(!counter.interrupt());
+}
+
+#[test]
+fn test_frame_counter_pal_timing() {
+    let mut counter = FrameCounter::new(Region::Pal);
+    let clocks = frame_clocks(&mut counter, 33254);
+    assert_eq!(clocks[0], (8313, FrameClock::Quarter));
+    assert_eq!(clocks[3], (33253, FrameClock::Half));
+}
+
+#[test]
+fn test_frame_irq_and_inhibit() {
+    let mut counter = FrameCounter::new(Region::Ntsc);
+    frame_clocks(&mut counter, 29827);
+    assert!(!counter.interrupt());
+    counter.tick();
+    assert!(counter.interrupt());
+    //setting the inhibit flag acknowledges it and suppresses new ones
+    counter.write(0b0100_0000, false);
+    assert!(!counter.interrupt());
+    frame_clocks(&mut counter, 29830 * 2);
+    assert!(!counter.interrupt());
+}
+
+#[test]
+fn test_status_register_read() {
+    let mut apu = APU::new();
+    apu.write_register(0x4015, 0b0001_1111);
+    apu.write_register(0x4003, 0b0000_1000);
+    apu.write_register(0x400B, 0b0000_1000);
+    assert_eq!(apu.read_status(), 0b0001_0101);
+    //the DMC fetches its first byte and finishes the one byte sample
+    apu.dmc.fill_sample_buffer(0);
+    assert_eq!(apu.read_status(), 0b0000_0101);
+
+    //reading acknowledges the frame interrupt but not the DMC one
+    let mut apu = APU::new();
+    apu.write_register(0x4010, 0b1000_0000);
+    apu.write_register(0x4015, 0b0001_0000);
+    apu.dmc.fill_sample_buffer(0);
+    for _ in 0..29830 {
+        apu.tick();
+    }
+    assert_eq!(apu.read_status(), 0b1100_0000);
+    assert_eq!(apu.read_status(), 0b1000_0000);
+    //writing $4015 acknowledges the DMC interrupt
+    apu.write_register(0x4015, 0x00);
+    assert_eq!(apu.read_status(), 0x00);
+    assert!(!apu.irq());
+}
+
+#[test]
+fn test_status_register_write_disables_channels() {
+    let mut apu = APU::new();
+    apu.write_register(0x4015, 0b0000_1111);
+    apu.write_register(0x4003, 0b0000_1000);
+    apu.write_register(0x4007, 0b0000_1000);
+    apu.write_register(0x400B, 0b0000_1000);
+    apu.write_register(0x400F, 0b0000_1000);
+    assert_eq!(apu.read_status(), 0b0000_1111);
+    apu.write_register(0x4015, 0b0000_0101);
+    assert_eq!(apu.read_status(), 0b0000_0101);
+    assert_eq!(apu.pulse2.length_counter(), 0);
+    assert_eq!(apu.noise.length_counter(), 0);
+}
+
+#[test]
+fn test_frame_counter_drives_length_counters() {
+    let mut apu = APU::new();
+    apu.write_register(0x4015, 0b0000_0001);
+    //length index 3 loads 2
+    apu.write_register(0x4003, 0b0001_1000);
+    assert_eq!(apu.pulse1.length_counter(), 2);
+    for _ in 0..14913 {
+        apu.tick();
+    }
+    assert_eq!(apu.pulse1.length_counter(), 1);
+    for _ in 14913..29829 {
+        apu.tick();
+    }
+    assert_eq!(apu.pulse1.length_counter(), 0);
+    assert_eq!(apu.read_status() & 0b0000_0001, 0);
+}
+
+#[test]
+fn test_mixer() {
+    assert_eq!(mixer::mix(0, 0, 0, 0, 0), 0.0);
+    let pulse = mixer::mix(15, 15, 0, 0, 0);
+    assert!((pulse - 0.2585).abs() < 0.001);
+    let tnd = mixer::mix(0, 0, 15, 15, 127);
+    assert!((tnd - 0.7417).abs() < 0.001);
+    //the DAC is non-linear: doubling the input less than doubles the output
+    let single = mixer::mix(8, 0, 0, 0, 0);
+    let double = mixer::mix(8, 8, 0, 0, 0);
+    assert!(double < single * 2.0);
+
+    let mut apu = APU::new();
+    apu.write_register(0x4011, 0x40);
+    assert_eq!(apu.output(), mixer::mix(0, 0, 15, 0, 0x40));
+}
+
+fn square_wave_rms(frequency: f64, sample_rate: u32) -> f32 {
+    let clock_rate = Region::Ntsc.cpu_clock_rate();
+    let mut resampler = Resampler::new(clock_rate, sample_rate);
+    let half_period = clock_rate / frequency / 2.0;
+    for clock in 0..(clock_rate as usize / 4) {
+        let high = (clock as f64 / half_period) as usize % 2 == 1;
+        resampler.clock(if high { 0.25 } else { 0.0 });
+    }
+    //skip the filters settling
+    let mut samples = vec![0.0; resampler.samples.len()];
+    let count = resampler.samples.pull_f32(&mut samples);
+    let tail = &samples[count / 2..count];
+    (tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32).sqrt()
+}
+
+#[test]
+fn test_resampler_output_rate() {
+    for rate in [44_100, 48_000] {
+        let mut resampler = Resampler::new(Region::Ntsc.cpu_clock_rate(), rate);
+        for _ in 0..(1_789_773 / 10) {
+            resampler.clock(0.0);
+        }
+        let expected = rate as usize / 10;
+        assert!(resampler.samples.len().abs_diff(expected) <= 1);
+    }
+}
+
+#[test]
+fn test_resampler_removes_dc() {
+    let mut resampler = Resampler::new(Region::Ntsc.cpu_clock_rate(), 44_100);
+    for _ in 0..1_789_773 {
+        resampler.clock(0.5);
+    }
+    let mut last = 1.0;
+    while let Some(sample) = resampler.samples.pop() {
+        last = sample;
+    }
+    assert!(last.abs() < 0.001);
+}
+
+#[test]
+fn test_resampler_is_band_limited() {
+    let audible = square_wave_rms(1_000.0, 44_100);
+    //a 30 kHz tone would alias down to 14.1 kHz without band limiting
+    let ultrasonic = square_wave_rms(30_000.0, 44_100);
+    assert!(audible > 0.05);
+    assert!(ultrasonic < audible * 0.05);
+}
+
+#[test]
+fn test_ring_buffer() {
+    let mut ring = RingBuffer::new(4);
+    assert!(ring.is_empty());
+    for sample in [0.1, 0.2, 0.3, 0.4, 0.5, 2.0] {
+        ring.push(sample);
+    }
+    //the oldest samples are dropped once full
+    assert_eq!(ring.len(), 4);
+    let mut out = [0.0; 2];
+    assert_eq!(ring.pull_f32(&mut out), 2);
+    assert_eq!(out, [0.3, 0.4]);
+    let mut out = [0i16; 4];
+    assert_eq!(ring.pull_i16(&mut out), 2);
+    assert_eq!(out[..2], [16383, i16::MAX]);
+    assert_eq!(ring.pop(), None);
+}
+
+#[test]
+fn test_apu_feeds_resampler() {
+    let mut apu = APU::new();
+    apu.set_sample_rate(48_000);
+    apu.write_register(0x4015, 0b0000_0001);
+    apu.write_register(0x4000, 0b1011_1111);
+    apu.write_register(0x4002, 0xFD);
+    apu.write_register(0x4003, 0b0000_1000);
+    for _ in 0..29830 {
+        apu.tick();
+    }
+    let mut out = [0i16; 1024];
+    let count = apu.pull_samples_i16(&mut out);
+    assert_eq!(count, 800);
+    assert!(out[..count].iter().any(|&s| s > 1000));
+    assert!(out[..count].iter().any(|&s| s < -1000));
+}
+
+#[test]
+fn test_wav_writer() {
+    let mut writer = wav::WavWriter::new(std::io::Cursor::new(Vec::new()), 48_000).unwrap();
+    writer.write_samples(&[0, 1, -1, i16::MAX]).unwrap();
+    writer.write_samples(&[i16::MIN]).unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    assert_eq!(bytes.len(), 44 + 10);
+    assert_eq!(&bytes[0..4], b"RIFF");
+    assert_eq!(&bytes[4..8], &46u32.to_le_bytes());
+    assert_eq!(&bytes[12..16], b"fmt ");
+    //PCM, mono, 48 kHz, 96000 bytes/s, 2 byte blocks, 16 bits
+    assert_eq!(&bytes[20..24], &[1, 0, 1, 0]);
+    assert_eq!(&bytes[24..28], &48_000u32.to_le_bytes());
+    assert_eq!(&bytes[28..32], &96_000u32.to_le_bytes());
+    assert_eq!(&bytes[32..36], &[2, 0, 16, 0]);
+    assert_eq!(&bytes[36..40], b"data");
+    assert_eq!(&bytes[40..44], &10u32.to_le_bytes());
+    assert_eq!(&bytes[44..48], &[0, 0, 1, 0]);
+    assert_eq!(&bytes[52..54], &i16::MIN.to_le_bytes());
+}
@@ -0,0 +1,182 @@
+use super::Region;
+
+// Output rates in CPU cycles per bit.
+const NTSC_RATES: [u16; 16] = [
+    428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54,
+];
+const PAL_RATES: [u16; 16] = [
+    398, 354, 316, 298, 276, 236, 210, 198, 176, 148, 132, 118, 98, 78, 66, 50,
+];
+
+pub struct DMC {
+    region: Region,
+    irq_enabled: bool,
+    loop_flag: bool,
+    timer: u16,
+    timer_period: u16,
+    output_level: u8,
+
+    sample_address: u16,
+    sample_length: u16,
+    current_address: u16,
+    bytes_remaining: u16,
+    sample_buffer: Option<u8>,
+
+    shift_register: u8,
+    bits_remaining: u8,
+    silence: bool,
+    interrupt: bool,
+}
+
+impl DMC {
+    pub fn new(region: Region) -> Self {
+        let mut dmc = Self {
+            region,
+            irq_enabled: false,
+            loop_flag: false,
+            timer: 0,
+            timer_period: 0,
+            output_level: 0,
+            sample_address: 0xC000,
+            sample_length: 1,
+            current_address: 0xC000,
+            bytes_remaining: 0,
+            sample_buffer: None,
+            shift_register: 0,
+            bits_remaining: 8,
+            silence: true,
+            interrupt: false,
+        };
+        dmc.set_rate(0);
+        dmc
+    }
+
+    // register is the offset from $4010, 0..=3
+    pub fn write_register(&mut self, register: u16, value: u8) {
+        match register {
+            //IL-- RRRR irq enable, loop, rate index
+            0 => {
+                self.irq_enabled = value & 0b1000_0000 != 0;
+                self.loop_flag = value & 0b0100_0000 != 0;
+                if !self.irq_enabled {
+                    self.interrupt = false;
+                }
+                self.set_rate(value & 0b0000_1111);
+            }
+            //-DDD DDDD direct load
+            1 => {
+                self.output_level = value & 0b0111_1111;
+            }
+            //AAAA AAAA sample address %11AAAAAA.AA000000
+            2 => {
+                self.sample_address = 0xC000 | ((value as u16) << 6);
+            }
+            //LLLL LLLL sample length %LLLL.LLLL0001
+            3 => {
+                self.sample_length = ((value as u16) << 4) | 1;
+            }
+            _ => panic!("dmc: register {} out of range", register),
+        }
+    }
+
+    fn set_rate(&mut self, index: u8) {
+        let rates = match self.region {
+            Region::Ntsc => &NTSC_RATES,
+            Region::Pal => &PAL_RATES,
+        };
+        // the timer is clocked every APU cycle, i.e. every second CPU cycle
+        self.timer_period = rates[index as usize] / 2;
+    }
+
+    // Clearing the $4015 bit stops the sample; setting it restarts the sample
+    // only if the previous one has finished. Either way the interrupt is
+    // acknowledged.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.interrupt = false;
+        if !enabled {
+            self.bytes_remaining = 0;
+        } else if self.bytes_remaining == 0 {
+            self.restart();
+        }
+    }
+
+    fn restart(&mut self) {
+        self.current_address = self.sample_address;
+        self.bytes_remaining = self.sample_length;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.bytes_remaining > 0
+    }
+
+    pub fn interrupt(&self) -> bool {
+        self.interrupt
+    }
+
+    // The memory reader lives on the CPU side of the bus: when this returns an
+    // address the CPU must read it (stalling itself) and hand the byte back
+    // through `fill_sample_buffer`.
+    pub fn sample_request(&self) -> Option<u16> {
+        if self.sample_buffer.is_none() && self.bytes_remaining > 0 {
+            Some(self.current_address)
+        } else {
+            None
+        }
+    }
+
+    pub fn fill_sample_buffer(&mut self, value: u8) {
+        self.sample_buffer = Some(value);
+        self.current_address = if self.current_address == 0xFFFF {
+            0x8000
+        } else {
+            self.current_address + 1
+        };
+        self.bytes_remaining -= 1;
+        if self.bytes_remaining == 0 {
+            if self.loop_flag {
+                self.restart();
+            } else if self.irq_enabled {
+                self.interrupt = true;
+            }
+        }
+    }
+
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.timer_period - 1;
+
+        if !self.silence {
+            if self.shift_register & 1 == 1 {
+                if self.output_level <= 125 {
+                    self.output_level += 2;
+                }
+            } else if self.output_level >= 2 {
+                self.output_level -= 2;
+            }
+        }
+        self.shift_register >>= 1;
+
+        self.bits_remaining -= 1;
+        if self.bits_remaining == 0 {
+            self.bits_remaining = 8;
+            match self.sample_buffer.take() {
+                Some(sample) => {
+                    self.silence = false;
+                    self.shift_register = sample;
+                }
+                None => self.silence = true,
+            }
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        self.output_level
+    }
+
+    pub fn bytes_remaining(&self) -> u16 {
+        self.bytes_remaining
+    }
+}
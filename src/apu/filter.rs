@@ -0,0 +1,53 @@
+use std::f32::consts::PI;
+
+// First-order IIR filters matching the analog stages after the NES DAC.
+pub enum FirstOrderFilter {
+    HighPass {
+        alpha: f32,
+        prev_in: f32,
+        prev_out: f32,
+    },
+    LowPass {
+        alpha: f32,
+        prev_out: f32,
+    },
+}
+
+impl FirstOrderFilter {
+    pub fn high_pass(sample_rate: f32, cutoff: f32) -> Self {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate;
+        FirstOrderFilter::HighPass {
+            alpha: rc / (rc + dt),
+            prev_in: 0.0,
+            prev_out: 0.0,
+        }
+    }
+
+    pub fn low_pass(sample_rate: f32, cutoff: f32) -> Self {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate;
+        FirstOrderFilter::LowPass {
+            alpha: dt / (rc + dt),
+            prev_out: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        match self {
+            FirstOrderFilter::HighPass {
+                alpha,
+                prev_in,
+                prev_out,
+            } => {
+                *prev_out = *alpha * (*prev_out + input - *prev_in);
+                *prev_in = input;
+                *prev_out
+            }
+            FirstOrderFilter::LowPass { alpha, prev_out } => {
+                *prev_out += *alpha * (input - *prev_out);
+                *prev_out
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::Region;
+
+// Step positions in CPU cycles since the sequencer was last reset.
+struct Timing {
+    quarter1: u32,
+    half1: u32,
+    quarter3: u32,
+    four_step_end: u32,
+    five_step_end: u32,
+}
+
+const NTSC_TIMING: Timing = Timing {
+    quarter1: 7457,
+    half1: 14913,
+    quarter3: 22371,
+    four_step_end: 29829,
+    five_step_end: 37281,
+};
+
+const PAL_TIMING: Timing = Timing {
+    quarter1: 8313,
+    half1: 16627,
+    quarter3: 24939,
+    four_step_end: 33253,
+    five_step_end: 41565,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FrameClock {
+    None,
+    Quarter,
+    // a half frame clock is always accompanied by a quarter frame clock
+    Half,
+}
+
+pub struct FrameCounter {
+    timing: &'static Timing,
+    five_step: bool,
+    irq_inhibit: bool,
+    interrupt: bool,
+    cycle: u32,
+    // $4017 writes reset the sequencer after a 3 or 4 cycle delay
+    reset_delay: u8,
+}
+
+impl FrameCounter {
+    pub fn new(region: Region) -> Self {
+        Self {
+            timing: match region {
+                Region::Ntsc => &NTSC_TIMING,
+                Region::Pal => &PAL_TIMING,
+            },
+            five_step: false,
+            irq_inhibit: false,
+            interrupt: false,
+            cycle: 0,
+            reset_delay: 0,
+        }
+    }
+
+    //MI-- ---- mode, irq inhibit
+    pub fn write(&mut self, value: u8, odd_cycle: bool) {
+        self.five_step = value & 0b1000_0000 != 0;
+        self.irq_inhibit = value & 0b0100_0000 != 0;
+        if self.irq_inhibit {
+            self.interrupt = false;
+        }
+        self.reset_delay = if odd_cycle { 3 } else { 4 };
+    }
+
+    pub fn interrupt(&self) -> bool {
+        self.interrupt
+    }
+
+    // Reading $4015 acknowledges the frame interrupt.
+    pub fn clear_interrupt(&mut self) {
+        self.interrupt = false;
+    }
+
+    pub fn five_step(&self) -> bool {
+        self.five_step
+    }
+
+    // Advances the sequencer by one CPU cycle.
+    pub fn tick(&mut self) -> FrameClock {
+        if self.reset_delay > 0 {
+            self.reset_delay -= 1;
+            if self.reset_delay == 0 {
+                self.cycle = 0;
+                // switching to 5-step mode clocks both units immediately
+                if self.five_step {
+                    return FrameClock::Half;
+                }
+                return FrameClock::None;
+            }
+        }
+
+        self.cycle += 1;
+        let timing = self.timing;
+        let raise_irq = !self.five_step && !self.irq_inhibit;
+        match self.cycle {
+            c if c == timing.quarter1 || c == timing.quarter3 => FrameClock::Quarter,
+            c if c == timing.half1 => FrameClock::Half,
+            c if c == timing.four_step_end - 1 && raise_irq => {
+                self.interrupt = true;
+                FrameClock::None
+            }
+            c if c == timing.four_step_end && !self.five_step => {
+                if raise_irq {
+                    self.interrupt = true;
+                }
+                FrameClock::Half
+            }
+            c if c == timing.four_step_end + 1 && !self.five_step => {
+                if raise_irq {
+                    self.interrupt = true;
+                }
+                self.cycle = 0;
+                FrameClock::None
+            }
+            c if c == timing.five_step_end && self.five_step => FrameClock::Half,
+            c if c == timing.five_step_end + 1 && self.five_step => {
+                self.cycle = 0;
+                FrameClock::None
+            }
+            _ => FrameClock::None,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use once_cell::sync::Lazy;
+
+// Lookup-table approximation of the 2A03's non-linear DAC, from the formulas
+// on the nesdev wiki. Both tables are indexed by the summed channel levels.
+static PULSE_TABLE: Lazy<[f32; 31]> = Lazy::new(|| {
+    let mut table = [0.0; 31];
+    for (n, entry) in table.iter_mut().enumerate().skip(1) {
+        *entry = 95.52 / (8128.0 / n as f32 + 100.0);
+    }
+    table
+});
+
+static TND_TABLE: Lazy<[f32; 203]> = Lazy::new(|| {
+    let mut table = [0.0; 203];
+    for (n, entry) in table.iter_mut().enumerate().skip(1) {
+        *entry = 163.67 / (24329.0 / n as f32 + 100.0);
+    }
+    table
+});
+
+// Mixes the raw channel levels into a single sample in 0.0..~1.0.
+pub fn mix(pulse1: u8, pulse2: u8, triangle: u8, noise: u8, dmc: u8) -> f32 {
+    let pulse = PULSE_TABLE[(pulse1 + pulse2) as usize];
+    let tnd = TND_TABLE[3 * triangle as usize + 2 * noise as usize + dmc as usize];
+    pulse + tnd
+}
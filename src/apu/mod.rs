@@ -0,0 +1,264 @@
+pub mod dmc;
+pub mod envelope;
+pub mod filter;
+pub mod frame_counter;
+pub mod length_counter;
+pub mod mixer;
+pub mod noise;
+pub mod pulse;
+pub mod resampler;
+pub mod ring_buffer;
+pub mod triangle;
+pub mod wav;
+
+use dmc::DMC;
+use frame_counter::{FrameClock, FrameCounter};
+use noise::Noise;
+use pulse::{Pulse, PulseChannel};
+use resampler::Resampler;
+use triangle::Triangle;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Region {
+    Ntsc,
+    Pal,
+}
+
+impl Region {
+    pub fn cpu_clock_rate(&self) -> f64 {
+        match self {
+            Region::Ntsc => 1_789_773.0,
+            Region::Pal => 1_662_607.0,
+        }
+    }
+
+    // Average length of a video frame in CPU cycles.
+    pub fn cpu_cycles_per_frame(&self) -> f64 {
+        match self {
+            Region::Ntsc => 29_780.5,
+            Region::Pal => 33_247.5,
+        }
+    }
+
+    // Exact video frame rate as numerator / denominator, from the master
+    // clock and the dots per frame (89341.5 on NTSC, 106392 on PAL).
+    pub fn frame_rate(&self) -> (u32, u32) {
+        match self {
+            Region::Ntsc => (39_375_000, 655_171),
+            Region::Pal => (53_203_425, 1_063_920),
+        }
+    }
+}
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+pub const CHANNEL_NAMES: [&str; 5] = ["pulse1", "pulse2", "triangle", "noise", "dmc"];
+
+pub struct APU {
+    region: Region,
+    pub pulse1: Pulse,
+    pub pulse2: Pulse,
+    pub triangle: Triangle,
+    pub noise: Noise,
+    pub dmc: DMC,
+    pub frame_counter: FrameCounter,
+    pub resampler: Resampler,
+    // one resampler per channel, only allocated while capturing channels
+    channel_resamplers: Option<Box<[Resampler; 5]>>,
+    // everything but the triangle runs at half the CPU clock
+    odd_cycle: bool,
+}
+
+impl Default for APU {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl APU {
+    pub fn new() -> Self {
+        Self::with_region(Region::Ntsc)
+    }
+
+    pub fn with_region(region: Region) -> Self {
+        Self {
+            region,
+            pulse1: Pulse::new(PulseChannel::One),
+            pulse2: Pulse::new(PulseChannel::Two),
+            triangle: Triangle::new(),
+            noise: Noise::new(region),
+            dmc: DMC::new(region),
+            frame_counter: FrameCounter::new(region),
+            resampler: Resampler::new(region.cpu_clock_rate(), DEFAULT_SAMPLE_RATE),
+            channel_resamplers: None,
+            odd_cycle: false,
+        }
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    pub fn write_register(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x4000..=0x4003 => self.pulse1.write_register(addr - 0x4000, value),
+            0x4004..=0x4007 => self.pulse2.write_register(addr - 0x4004, value),
+            0x4008..=0x400B => self.triangle.write_register(addr - 0x4008, value),
+            0x400C..=0x400F => self.noise.write_register(addr - 0x400C, value),
+            0x4010..=0x4013 => self.dmc.write_register(addr - 0x4010, value),
+            //---D NT21 channel enables
+            0x4015 => {
+                self.pulse1.set_enabled(value & 0b0000_0001 != 0);
+                self.pulse2.set_enabled(value & 0b0000_0010 != 0);
+                self.triangle.set_enabled(value & 0b0000_0100 != 0);
+                self.noise.set_enabled(value & 0b0000_1000 != 0);
+                self.dmc.set_enabled(value & 0b0001_0000 != 0);
+            }
+            0x4017 => self.frame_counter.write(value, self.odd_cycle),
+            _ => {}
+        }
+    }
+
+    //IF-D NT21 dmc irq, frame irq, dmc active, length counters > 0
+    pub fn read_status(&mut self) -> u8 {
+        let mut status = 0;
+        if self.pulse1.length_counter() > 0 {
+            status |= 0b0000_0001;
+        }
+        if self.pulse2.length_counter() > 0 {
+            status |= 0b0000_0010;
+        }
+        if self.triangle.length_counter() > 0 {
+            status |= 0b0000_0100;
+        }
+        if self.noise.length_counter() > 0 {
+            status |= 0b0000_1000;
+        }
+        if self.dmc.is_active() {
+            status |= 0b0001_0000;
+        }
+        if self.frame_counter.interrupt() {
+            status |= 0b0100_0000;
+        }
+        if self.dmc.interrupt() {
+            status |= 0b1000_0000;
+        }
+        self.frame_counter.clear_interrupt();
+        status
+    }
+
+    // Advances the APU by one CPU cycle.
+    pub fn tick(&mut self) {
+        self.triangle.clock_timer();
+        if self.odd_cycle {
+            self.pulse1.clock_timer();
+            self.pulse2.clock_timer();
+            self.noise.clock_timer();
+            self.dmc.clock_timer();
+        }
+        self.odd_cycle = !self.odd_cycle;
+
+        match self.frame_counter.tick() {
+            FrameClock::Quarter => self.clock_quarter_frame(),
+            FrameClock::Half => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            }
+            FrameClock::None => {}
+        }
+
+        self.resampler.clock(self.output());
+        if self.channel_resamplers.is_some() {
+            let levels = self.channel_outputs();
+            if let Some(resamplers) = self.channel_resamplers.as_mut() {
+                for (resampler, level) in resamplers.iter_mut().zip(levels) {
+                    resampler.clock(level);
+                }
+            }
+        }
+    }
+
+    // Each channel run through the mixer on its own, in CHANNEL_NAMES order.
+    pub fn channel_outputs(&self) -> [f32; 5] {
+        [
+            mixer::mix(self.pulse1.output(), 0, 0, 0, 0),
+            mixer::mix(0, self.pulse2.output(), 0, 0, 0),
+            mixer::mix(0, 0, self.triangle.output(), 0, 0),
+            mixer::mix(0, 0, 0, self.noise.output(), 0),
+            mixer::mix(0, 0, 0, 0, self.dmc.output()),
+        ]
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.resampler.set_sample_rate(sample_rate);
+        if let Some(resamplers) = self.channel_resamplers.as_mut() {
+            for resampler in resamplers.iter_mut() {
+                resampler.set_sample_rate(sample_rate);
+            }
+        }
+    }
+
+    // Starts or stops resampling every channel separately alongside the mix.
+    pub fn set_channel_capture(&mut self, enabled: bool) {
+        if !enabled {
+            self.channel_resamplers = None;
+        } else if self.channel_resamplers.is_none() {
+            let clock_rate = self.region.cpu_clock_rate();
+            let sample_rate = self.resampler.sample_rate();
+            self.channel_resamplers = Some(Box::new(std::array::from_fn(|_| {
+                Resampler::new(clock_rate, sample_rate)
+            })));
+        }
+    }
+
+    pub fn pull_channel_samples_i16(&mut self, channel: usize, out: &mut [i16]) -> usize {
+        match self.channel_resamplers.as_mut() {
+            Some(resamplers) => resamplers[channel].samples.pull_i16(out),
+            None => 0,
+        }
+    }
+
+    pub fn pull_samples_f32(&mut self, out: &mut [f32]) -> usize {
+        self.resampler.samples.pull_f32(out)
+    }
+
+    pub fn pull_samples_i16(&mut self, out: &mut [i16]) -> usize {
+        self.resampler.samples.pull_i16(out)
+    }
+
+    // Level-triggered IRQ line into the CPU.
+    pub fn irq(&self) -> bool {
+        self.dmc.interrupt() || self.frame_counter.interrupt()
+    }
+
+    // The current mixed output level, one sample per CPU cycle.
+    pub fn output(&self) -> f32 {
+        mixer::mix(
+            self.pulse1.output(),
+            self.pulse2.output(),
+            self.triangle.output(),
+            self.noise.output(),
+            self.dmc.output(),
+        )
+    }
+
+    // Envelopes and the triangle's linear counter are clocked on quarter frames.
+    pub fn clock_quarter_frame(&mut self) {
+        self.pulse1.clock_quarter_frame();
+        self.pulse2.clock_quarter_frame();
+        self.triangle.clock_quarter_frame();
+        self.noise.clock_quarter_frame();
+    }
+
+    // Length counters and sweep units are clocked on half frames.
+    pub fn clock_half_frame(&mut self) {
+        self.pulse1.clock_half_frame();
+        self.pulse2.clock_half_frame();
+        self.triangle.clock_half_frame();
+        self.noise.clock_half_frame();
+    }
+}
+
+#[cfg(test)]
+#[path = "./apu_test.rs"]
+mod apu_tests;
@@ -0,0 +1,104 @@
+use super::envelope::Envelope;
+use super::length_counter::LengthCounter;
+use super::Region;
+
+// Timer periods in CPU cycles.
+const NTSC_PERIODS: [u16; 16] = [
+    4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068,
+];
+const PAL_PERIODS: [u16; 16] = [
+    4, 8, 14, 30, 60, 88, 118, 148, 188, 236, 354, 472, 708, 944, 1890, 3778,
+];
+
+pub struct Noise {
+    region: Region,
+    // 15-bit linear feedback shift register
+    shift_register: u16,
+    // short mode taps bit 6 instead of bit 1, giving a 93-step metallic loop
+    short_mode: bool,
+    timer: u16,
+    timer_period: u16,
+    envelope: Envelope,
+    length_counter: LengthCounter,
+}
+
+impl Noise {
+    pub fn new(region: Region) -> Self {
+        Self {
+            region,
+            shift_register: 1,
+            short_mode: false,
+            timer: 0,
+            timer_period: 0,
+            envelope: Envelope::default(),
+            length_counter: LengthCounter::default(),
+        }
+    }
+
+    // register is the offset from $400C, 0..=3
+    pub fn write_register(&mut self, register: u16, value: u8) {
+        match register {
+            //--LC VVVV length halt / envelope loop, constant volume, volume
+            0 => {
+                self.length_counter.set_halt(value & 0b0010_0000 != 0);
+                self.envelope.write(value);
+            }
+            //unused
+            1 => {}
+            //M--- PPPP mode, period index
+            2 => {
+                self.short_mode = value & 0b1000_0000 != 0;
+                let periods = match self.region {
+                    Region::Ntsc => &NTSC_PERIODS,
+                    Region::Pal => &PAL_PERIODS,
+                };
+                // the timer is clocked every APU cycle, i.e. every second CPU cycle
+                self.timer_period = periods[(value & 0b0000_1111) as usize] / 2;
+            }
+            //LLLL L--- length load
+            3 => {
+                self.length_counter.load(value >> 3);
+                self.envelope.restart();
+            }
+            _ => panic!("noise: register {} out of range", register),
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.length_counter.set_enabled(enabled);
+    }
+
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.timer_period.saturating_sub(1);
+        let tap = if self.short_mode { 6 } else { 1 };
+        let feedback = (self.shift_register ^ (self.shift_register >> tap)) & 1;
+        self.shift_register = (self.shift_register >> 1) | (feedback << 14);
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+    }
+
+    pub fn output(&self) -> u8 {
+        if !self.length_counter.is_active() || self.shift_register & 1 == 1 {
+            return 0;
+        }
+        self.envelope.output()
+    }
+
+    pub fn length_counter(&self) -> u8 {
+        self.length_counter.value()
+    }
+
+    pub fn shift_register(&self) -> u16 {
+        self.shift_register
+    }
+}
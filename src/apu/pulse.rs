@@ -0,0 +1,161 @@
+use super::envelope::Envelope;
+use super::length_counter::LengthCounter;
+
+// The sequencer counts down from 0, so each row is read as 0, 7, 6, ..., 1.
+const DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 0, 0, 0, 0, 0, 0, 1],
+    [0, 0, 0, 0, 0, 0, 1, 1],
+    [0, 0, 0, 0, 1, 1, 1, 1],
+    [1, 1, 1, 1, 1, 1, 0, 0],
+];
+
+// Pulse 1 negates the sweep change with ones' complement (subtracting one
+// extra), pulse 2 with two's complement.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PulseChannel {
+    One,
+    Two,
+}
+
+#[derive(Default)]
+struct Sweep {
+    enabled: bool,
+    period: u8,
+    negate: bool,
+    shift: u8,
+    reload: bool,
+    divider: u8,
+}
+
+pub struct Pulse {
+    channel: PulseChannel,
+    duty: u8,
+    sequence: u8,
+    timer: u16,
+    timer_period: u16,
+    envelope: Envelope,
+    length_counter: LengthCounter,
+    sweep: Sweep,
+}
+
+impl Pulse {
+    pub fn new(channel: PulseChannel) -> Self {
+        Self {
+            channel,
+            duty: 0,
+            sequence: 0,
+            timer: 0,
+            timer_period: 0,
+            envelope: Envelope::default(),
+            length_counter: LengthCounter::default(),
+            sweep: Sweep::default(),
+        }
+    }
+
+    // register is the offset from the channel's base address, 0..=3
+    pub fn write_register(&mut self, register: u16, value: u8) {
+        match register {
+            //DDLC VVVV duty, length halt / envelope loop, constant volume, volume
+            0 => {
+                self.duty = value >> 6;
+                self.length_counter.set_halt(value & 0b0010_0000 != 0);
+                self.envelope.write(value);
+            }
+            //EPPP NSSS sweep enable, period, negate, shift
+            1 => {
+                self.sweep.enabled = value & 0b1000_0000 != 0;
+                self.sweep.period = (value >> 4) & 0b0111;
+                self.sweep.negate = value & 0b0000_1000 != 0;
+                self.sweep.shift = value & 0b0000_0111;
+                self.sweep.reload = true;
+            }
+            //TTTT TTTT timer low
+            2 => {
+                self.timer_period = (self.timer_period & 0x0700) | value as u16;
+            }
+            //LLLL LTTT length load, timer high
+            3 => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((value as u16 & 0b0111) << 8);
+                self.length_counter.load(value >> 3);
+                self.sequence = 0;
+                self.envelope.restart();
+            }
+            _ => panic!("pulse: register {} out of range", register),
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.length_counter.set_enabled(enabled);
+    }
+
+    // Clocked every APU cycle (every second CPU cycle).
+    pub fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            self.sequence = (self.sequence + 7) % 8;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+        self.clock_sweep();
+    }
+
+    fn clock_sweep(&mut self) {
+        if self.sweep.divider == 0
+            && self.sweep.enabled
+            && self.sweep.shift != 0
+            && !self.is_muted()
+        {
+            self.timer_period = self.target_period();
+        }
+        if self.sweep.divider == 0 || self.sweep.reload {
+            self.sweep.divider = self.sweep.period;
+            self.sweep.reload = false;
+        } else {
+            self.sweep.divider -= 1;
+        }
+    }
+
+    // The sweep unit computes the target continuously, even when disabled.
+    fn target_period(&self) -> u16 {
+        let change = self.timer_period >> self.sweep.shift;
+        if !self.sweep.negate {
+            return self.timer_period + change;
+        }
+        match self.channel {
+            PulseChannel::One => self.timer_period.saturating_sub(change + 1),
+            PulseChannel::Two => self.timer_period.saturating_sub(change),
+        }
+    }
+
+    // Periods below 8 or a target above $7FF silence the channel whether or
+    // not the sweep is enabled.
+    pub fn is_muted(&self) -> bool {
+        self.timer_period < 8 || self.target_period() > 0x7FF
+    }
+
+    pub fn output(&self) -> u8 {
+        if !self.length_counter.is_active()
+            || self.is_muted()
+            || DUTY_TABLE[self.duty as usize][self.sequence as usize] == 0
+        {
+            return 0;
+        }
+        self.envelope.output()
+    }
+
+    pub fn length_counter(&self) -> u8 {
+        self.length_counter.value()
+    }
+
+    pub fn timer_period(&self) -> u16 {
+        self.timer_period
+    }
+}
@@ -0,0 +1,124 @@
+use std::f64::consts::PI;
+
+use once_cell::sync::Lazy;
+
+use super::filter::FirstOrderFilter;
+use super::ring_buffer::RingBuffer;
+
+// Band-limited step synthesis in the style of blip_buf: every change in the
+// APU's output level is added to the output as a windowed-sinc step placed at
+// its fractional sample position, and the steps are integrated back into a
+// waveform. Nothing above the host Nyquist frequency survives, so the 1.79 MHz
+// input can be decimated without aliasing.
+const KERNEL_WIDTH: usize = 16;
+const KERNEL_PHASES: usize = 64;
+// fraction of the output Nyquist frequency the kernel passes
+const CUTOFF: f64 = 0.9;
+// half a second of audio at 48 kHz
+const RING_CAPACITY: usize = 24_000;
+
+static KERNELS: Lazy<Vec<[f32; KERNEL_WIDTH]>> = Lazy::new(|| {
+    let half = (KERNEL_WIDTH / 2) as f64;
+    (0..KERNEL_PHASES)
+        .map(|phase| {
+            let offset = phase as f64 / KERNEL_PHASES as f64;
+            let mut kernel = [0.0f64; KERNEL_WIDTH];
+            for (k, tap) in kernel.iter_mut().enumerate() {
+                let x = k as f64 - half - offset;
+                let sinc = if x == 0.0 {
+                    1.0
+                } else {
+                    (PI * CUTOFF * x).sin() / (PI * CUTOFF * x)
+                };
+                // Blackman window over the kernel span
+                let n = (x + half + 1.0) / (KERNEL_WIDTH as f64 + 1.0);
+                let window = 0.42 - 0.5 * (2.0 * PI * n).cos() + 0.08 * (4.0 * PI * n).cos();
+                *tap = sinc * window;
+            }
+            // every phase must integrate to exactly one step
+            let sum: f64 = kernel.iter().sum();
+            let mut taps = [0.0f32; KERNEL_WIDTH];
+            for (tap, value) in taps.iter_mut().zip(kernel.iter()) {
+                *tap = (value / sum) as f32;
+            }
+            taps
+        })
+        .collect()
+});
+
+pub struct Resampler {
+    clock_rate: f64,
+    sample_rate: u32,
+    // output samples per input clock
+    factor: f64,
+    // position of the current input clock within the next output sample
+    time: f64,
+    last_level: f32,
+    deltas: [f32; KERNEL_WIDTH + 1],
+    integrator: f32,
+    filters: [FirstOrderFilter; 3],
+    pub samples: RingBuffer,
+}
+
+impl Resampler {
+    pub fn new(clock_rate: f64, sample_rate: u32) -> Self {
+        Self {
+            clock_rate,
+            sample_rate,
+            factor: sample_rate as f64 / clock_rate,
+            time: 0.0,
+            last_level: 0.0,
+            deltas: [0.0; KERNEL_WIDTH + 1],
+            integrator: 0.0,
+            filters: Self::build_filters(sample_rate),
+            samples: RingBuffer::new(RING_CAPACITY),
+        }
+    }
+
+    // The NES output stage: high-pass at 90 Hz and 440 Hz, low-pass at 14 kHz.
+    fn build_filters(sample_rate: u32) -> [FirstOrderFilter; 3] {
+        let rate = sample_rate as f32;
+        [
+            FirstOrderFilter::high_pass(rate, 90.0),
+            FirstOrderFilter::high_pass(rate, 440.0),
+            FirstOrderFilter::low_pass(rate, 14_000.0),
+        ]
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        *self = Self::new(self.clock_rate, sample_rate);
+    }
+
+    // Feeds the mixer output for one input clock.
+    pub fn clock(&mut self, level: f32) {
+        let delta = level - self.last_level;
+        if delta != 0.0 {
+            self.last_level = level;
+            let phase = (self.time * KERNEL_PHASES as f64) as usize;
+            for (slot, tap) in self.deltas.iter_mut().zip(KERNELS[phase].iter()) {
+                *slot += delta * tap;
+            }
+        }
+        self.time += self.factor;
+        while self.time >= 1.0 {
+            self.time -= 1.0;
+            self.emit_sample();
+        }
+    }
+
+    fn emit_sample(&mut self) {
+        self.integrator += self.deltas[0];
+        self.deltas.rotate_left(1);
+        self.deltas[KERNEL_WIDTH] = 0.0;
+
+        let mut sample = self.integrator;
+        for filter in self.filters.iter_mut() {
+            sample = filter.process(sample);
+        }
+        self.samples.push(sample);
+    }
+}
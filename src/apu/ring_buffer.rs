@@ -0,0 +1,80 @@
+// Fixed-size sample queue between the emulation and an audio frontend. When
+// the frontend falls behind the oldest samples are overwritten.
+pub struct RingBuffer {
+    data: Vec<f32>,
+    read: usize,
+    len: usize,
+}
+
+impl RingBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            data: vec![0.0; capacity],
+            read: 0,
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.read = 0;
+        self.len = 0;
+    }
+
+    pub fn push(&mut self, sample: f32) {
+        let capacity = self.data.len();
+        let write = (self.read + self.len) % capacity;
+        self.data[write] = sample;
+        if self.len == capacity {
+            self.read = (self.read + 1) % capacity;
+        } else {
+            self.len += 1;
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<f32> {
+        if self.len == 0 {
+            return None;
+        }
+        let sample = self.data[self.read];
+        self.read = (self.read + 1) % self.data.len();
+        self.len -= 1;
+        Some(sample)
+    }
+
+    // Fills as much of `out` as there are samples for and returns the count.
+    pub fn pull_f32(&mut self, out: &mut [f32]) -> usize {
+        let mut count = 0;
+        for slot in out.iter_mut() {
+            match self.pop() {
+                Some(sample) => *slot = sample,
+                None => break,
+            }
+            count += 1;
+        }
+        count
+    }
+
+    pub fn pull_i16(&mut self, out: &mut [i16]) -> usize {
+        let mut count = 0;
+        for slot in out.iter_mut() {
+            match self.pop() {
+                Some(sample) => *slot = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16,
+                None => break,
+            }
+            count += 1;
+        }
+        count
+    }
+}
@@ -0,0 +1,115 @@
+use super::length_counter::LengthCounter;
+
+const SEQUENCE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12,
+    13, 14, 15,
+];
+
+pub struct Triangle {
+    sequence: u8,
+    timer: u16,
+    timer_period: u16,
+    length_counter: LengthCounter,
+    control: bool,
+    linear_counter: u8,
+    linear_reload_value: u8,
+    linear_reload: bool,
+    // Periods of 0 and 1 produce a tone far above hearing that only shows up
+    // as a pop in the mix; when set the sequencer is held instead.
+    pub silence_ultrasonic: bool,
+}
+
+impl Default for Triangle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Triangle {
+    pub fn new() -> Self {
+        Self {
+            sequence: 0,
+            timer: 0,
+            timer_period: 0,
+            length_counter: LengthCounter::default(),
+            control: false,
+            linear_counter: 0,
+            linear_reload_value: 0,
+            linear_reload: false,
+            silence_ultrasonic: true,
+        }
+    }
+
+    // register is the offset from $4008, 0..=3
+    pub fn write_register(&mut self, register: u16, value: u8) {
+        match register {
+            //CRRR RRRR length halt / linear control, linear counter reload
+            0 => {
+                self.control = value & 0b1000_0000 != 0;
+                self.length_counter.set_halt(self.control);
+                self.linear_reload_value = value & 0b0111_1111;
+            }
+            //unused
+            1 => {}
+            //TTTT TTTT timer low
+            2 => {
+                self.timer_period = (self.timer_period & 0x0700) | value as u16;
+            }
+            //LLLL LTTT length load, timer high
+            3 => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((value as u16 & 0b0111) << 8);
+                self.length_counter.load(value >> 3);
+                self.linear_reload = true;
+            }
+            _ => panic!("triangle: register {} out of range", register),
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.length_counter.set_enabled(enabled);
+    }
+
+    // Unlike the other channels the triangle timer runs at the CPU clock.
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.timer_period;
+        if self.silence_ultrasonic && self.timer_period < 2 {
+            return;
+        }
+        if self.linear_counter > 0 && self.length_counter.is_active() {
+            self.sequence = (self.sequence + 1) % 32;
+        }
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        if self.linear_reload {
+            self.linear_counter = self.linear_reload_value;
+        } else if self.linear_counter > 0 {
+            self.linear_counter -= 1;
+        }
+        if !self.control {
+            self.linear_reload = false;
+        }
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+    }
+
+    // A halted sequencer keeps emitting its current step instead of
+    // dropping to zero.
+    pub fn output(&self) -> u8 {
+        SEQUENCE[self.sequence as usize]
+    }
+
+    pub fn length_counter(&self) -> u8 {
+        self.length_counter.value()
+    }
+
+    pub fn linear_counter(&self) -> u8 {
+        self.linear_counter
+    }
+}
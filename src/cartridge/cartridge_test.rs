@@ -0,0 +1,45 @@
+use super::*;
+
+fn raw_rom(prg_banks: u8, chr_banks: u8, flags6: u8) -> Vec<u8> {
+    let mut raw = vec![b'N', b'E', b'S', 0x1A, prg_banks, chr_banks, flags6];
+    raw.resize(HEADER_SIZE, 0);
+    if flags6 & 0b100 != 0 {
+        raw.extend(vec![0xEE; TRAINER_SIZE]);
+    }
+    raw.extend(vec![0xAA; prg_banks as usize * 0x4000]);
+    raw.extend(vec![0xBB; chr_banks as usize * 0x2000]);
+    raw
+}
+
+#[test]
+fn test_splits_prg_and_chr() {
+    let rom = Rom::new(&raw_rom(2, 1, 0)).unwrap();
+    assert_eq!(rom.prg_rom.len(), 0x8000);
+    assert!(rom.prg_rom.iter().all(|&byte| byte == 0xAA));
+    assert_eq!(rom.chr_rom.len(), 0x2000);
+    assert!(rom.chr_rom.iter().all(|&byte| byte == 0xBB));
+    assert!(rom.trainer.is_none());
+}
+
+#[test]
+fn test_skips_trainer() {
+    let rom = Rom::new(&raw_rom(1, 0, 0b100)).unwrap();
+    assert_eq!(rom.trainer.unwrap().len(), TRAINER_SIZE);
+    assert!(rom.prg_rom.iter().all(|&byte| byte == 0xAA));
+    assert!(rom.chr_rom.is_empty());
+}
+
+#[test]
+fn test_truncated_file() {
+    let mut raw = raw_rom(1, 1, 0);
+    raw.truncate(raw.len() - 1);
+    assert!(Rom::new(&raw).is_err());
+}
+
+#[test]
+fn test_oversized_rom_sizes() {
+    //NES 2.0 with 2^63 bytes each of PRG and CHR ROM
+    let mut raw = vec![b'N', b'E', b'S', 0x1A, 0xFC, 0xFC, 0, 0x08, 0, 0xFF];
+    raw.resize(HEADER_SIZE, 0);
+    assert_eq!(Rom::new(&raw).err(), Some("ROM size too large".to_string()));
+}
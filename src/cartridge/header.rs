@@ -0,0 +1,134 @@
+const NES_TAG: [u8; 4] = [b'N', b'E', b'S', 0x1A];
+pub const HEADER_SIZE: usize = 16;
+const PRG_ROM_PAGE_SIZE: usize = 16 * 1024;
+const CHR_ROM_PAGE_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mirroring {
+    Horizontal,
+    Vertical,
+    FourScreen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    Ntsc,
+    Pal,
+    MultiRegion,
+    Dendy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub nes2: bool,
+    pub mapper: u16,
+    pub submapper: u8,
+    pub prg_rom_size: usize,
+    pub chr_rom_size: usize,
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+    pub chr_ram_size: usize,
+    pub chr_nvram_size: usize,
+    pub mirroring: Mirroring,
+    pub battery: bool,
+    pub trainer: bool,
+    pub console_type: u8,
+    pub timing: Timing,
+    // NES 2.0 byte 15, 0 when unspecified or for plain iNES files
+    pub default_expansion_device: u8,
+}
+
+impl Header {
+    pub fn parse(raw: &[u8]) -> Result<Header, String> {
+        if raw.len() < HEADER_SIZE || raw[0..4] != NES_TAG {
+            return Err("File is not in iNES file format".to_string());
+        }
+
+        let mirroring = if raw[6] & 0b1000 != 0 {
+            Mirroring::FourScreen
+        } else if raw[6] & 0b1 != 0 {
+            Mirroring::Vertical
+        } else {
+            Mirroring::Horizontal
+        };
+        let battery = raw[6] & 0b10 != 0;
+        let trainer = raw[6] & 0b100 != 0;
+        let console_type = raw[7] & 0b11;
+        let mapper_low = ((raw[7] & 0xF0) | (raw[6] >> 4)) as u16;
+        let nes2 = raw[7] & 0b0000_1100 == 0b0000_1000;
+
+        if !nes2 {
+            return Ok(Header {
+                nes2,
+                mapper: mapper_low,
+                submapper: 0,
+                prg_rom_size: raw[4] as usize * PRG_ROM_PAGE_SIZE,
+                chr_rom_size: raw[5] as usize * CHR_ROM_PAGE_SIZE,
+                // iNES 1.0 counts PRG RAM in 8 KiB units, with 0 meaning one
+                prg_ram_size: raw[8].max(1) as usize * 8 * 1024,
+                prg_nvram_size: 0,
+                chr_ram_size: if raw[5] == 0 { CHR_ROM_PAGE_SIZE } else { 0 },
+                chr_nvram_size: 0,
+                mirroring,
+                battery,
+                trainer,
+                console_type,
+                timing: if raw[9] & 1 != 0 {
+                    Timing::Pal
+                } else {
+                    Timing::Ntsc
+                },
+                default_expansion_device: 0,
+            });
+        }
+
+        Ok(Header {
+            nes2,
+            mapper: mapper_low | ((raw[8] as u16 & 0x0F) << 8),
+            submapper: raw[8] >> 4,
+            prg_rom_size: rom_size(raw[4], raw[9] & 0x0F, PRG_ROM_PAGE_SIZE)?,
+            chr_rom_size: rom_size(raw[5], raw[9] >> 4, CHR_ROM_PAGE_SIZE)?,
+            prg_ram_size: ram_size(raw[10] & 0x0F),
+            prg_nvram_size: ram_size(raw[10] >> 4),
+            chr_ram_size: ram_size(raw[11] & 0x0F),
+            chr_nvram_size: ram_size(raw[11] >> 4),
+            mirroring,
+            battery,
+            trainer,
+            console_type,
+            timing: match raw[12] & 0b11 {
+                0 => Timing::Ntsc,
+                1 => Timing::Pal,
+                2 => Timing::MultiRegion,
+                _ => Timing::Dendy,
+            },
+            default_expansion_device: raw[15] & 0b0011_1111,
+        })
+    }
+}
+
+// NES 2.0 sizes: an MSB nibble of $F switches to exponent-multiplier notation
+fn rom_size(lsb: u8, msb: u8, page_size: usize) -> Result<usize, String> {
+    if msb == 0x0F {
+        let exponent = (lsb >> 2) as u32;
+        let multiplier = (lsb & 0b11) as usize * 2 + 1;
+        return 2usize
+            .checked_pow(exponent)
+            .and_then(|size| size.checked_mul(multiplier))
+            .ok_or("ROM size too large".to_string());
+    }
+    Ok(((msb as usize) << 8 | lsb as usize) * page_size)
+}
+
+// NES 2.0 RAM sizes are stored as a shift count: 64 << n bytes, 0 for none
+fn ram_size(shift: u8) -> usize {
+    if shift == 0 {
+        0
+    } else {
+        64 << shift
+    }
+}
+
+#[cfg(test)]
+#[path = "./header_test.rs"]
+mod header_tests;
@@ -0,0 +1,82 @@
+use super::*;
+
+fn raw_header(bytes: &[(usize, u8)]) -> Vec<u8> {
+    let mut raw = vec![0u8; HEADER_SIZE];
+    raw[0..4].copy_from_slice(&NES_TAG);
+    for &(index, value) in bytes {
+        raw[index] = value;
+    }
+    raw
+}
+
+#[test]
+fn test_rejects_missing_tag() {
+    assert!(Header::parse(&[0; HEADER_SIZE]).is_err());
+    assert!(Header::parse(&NES_TAG).is_err());
+}
+
+#[test]
+fn test_ines_header() {
+    let header = Header::parse(&raw_header(&[(4, 2), (5, 1), (6, 0x13), (7, 0x40)])).unwrap();
+    assert!(!header.nes2);
+    assert_eq!(header.mapper, 0x41);
+    assert_eq!(header.prg_rom_size, 32 * 1024);
+    assert_eq!(header.chr_rom_size, 8 * 1024);
+    assert_eq!(header.chr_ram_size, 0);
+    assert_eq!(header.prg_ram_size, 8 * 1024);
+    assert_eq!(header.mirroring, Mirroring::Vertical);
+    assert!(header.battery);
+    assert!(!header.trainer);
+    assert_eq!(header.timing, Timing::Ntsc);
+    assert_eq!(header.default_expansion_device, 0);
+}
+
+#[test]
+fn test_ines_chr_ram_and_pal() {
+    let header = Header::parse(&raw_header(&[(4, 1), (6, 0x08), (9, 1)])).unwrap();
+    assert_eq!(header.chr_rom_size, 0);
+    assert_eq!(header.chr_ram_size, 8 * 1024);
+    assert_eq!(header.mirroring, Mirroring::FourScreen);
+    assert_eq!(header.timing, Timing::Pal);
+}
+
+#[test]
+fn test_nes2_header() {
+    let header = Header::parse(&raw_header(&[
+        (4, 0x02),
+        (5, 0x00),
+        (6, 0x40),
+        (7, 0x18),
+        (8, 0x31),
+        (9, 0x01),
+        (10, 0x70),
+        (11, 0x07),
+        (12, 0x01),
+        (15, 0x02),
+    ]))
+    .unwrap();
+    assert!(header.nes2);
+    assert_eq!(header.mapper, 0x114);
+    assert_eq!(header.submapper, 3);
+    assert_eq!(header.prg_rom_size, 0x102 * 16 * 1024);
+    assert_eq!(header.chr_rom_size, 0);
+    assert_eq!(header.prg_ram_size, 0);
+    assert_eq!(header.prg_nvram_size, 8 * 1024);
+    assert_eq!(header.chr_ram_size, 8 * 1024);
+    assert_eq!(header.timing, Timing::Pal);
+    assert_eq!(header.default_expansion_device, 0x02);
+}
+
+#[test]
+fn test_nes2_exponent_rom_size() {
+    //2^5 * (2*2+1) bytes
+    let header = Header::parse(&raw_header(&[(4, 0b0001_0110), (7, 0x08), (9, 0x0F)])).unwrap();
+    assert_eq!(header.prg_rom_size, 160);
+}
+
+#[test]
+fn test_nes2_rom_size_too_large() {
+    //2^63 * (2*3+1) bytes
+    let raw = raw_header(&[(4, 0xFF), (7, 0x08), (9, 0x0F)]);
+    assert_eq!(Header::parse(&raw), Err("ROM size too large".to_string()));
+}
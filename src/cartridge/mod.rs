@@ -0,0 +1,42 @@
+pub mod header;
+
+use header::{Header, HEADER_SIZE};
+
+const TRAINER_SIZE: usize = 512;
+
+pub struct Rom {
+    pub header: Header,
+    pub trainer: Option<Vec<u8>>,
+    pub prg_rom: Vec<u8>,
+    pub chr_rom: Vec<u8>,
+}
+
+impl Rom {
+    pub fn new(raw: &[u8]) -> Result<Rom, String> {
+        let header = Header::parse(raw)?;
+        let prg_start = HEADER_SIZE + if header.trainer { TRAINER_SIZE } else { 0 };
+        let chr_start = prg_start
+            .checked_add(header.prg_rom_size)
+            .ok_or("ROM size too large")?;
+        let chr_end = chr_start
+            .checked_add(header.chr_rom_size)
+            .ok_or("ROM size too large")?;
+        if raw.len() < chr_end {
+            return Err(format!(
+                "File is truncated: expected {} bytes, got {}",
+                chr_end,
+                raw.len()
+            ));
+        }
+        Ok(Rom {
+            trainer: header.trainer.then(|| raw[HEADER_SIZE..prg_start].to_vec()),
+            prg_rom: raw[prg_start..chr_start].to_vec(),
+            chr_rom: raw[chr_start..chr_end].to_vec(),
+            header,
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "./cartridge_test.rs"]
+mod cartridge_tests;
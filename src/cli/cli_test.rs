@@ -0,0 +1,240 @@
+use super::*;
+use crate::headless::TempDir;
+use crate::render::frame::Frame;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+fn write_rom(dir: &TempDir, name: &str, program: &[u8], mapper: u8) -> PathBuf {
+    let mut raw = vec![b'N', b'E', b'S', 0x1A, 1, 0, mapper << 4, 0];
+    raw.resize(16, 0);
+    let mut prg = vec![0; 0x4000];
+    prg[..program.len()].copy_from_slice(program);
+    prg[0x3FFC] = 0x00;
+    prg[0x3FFD] = 0x80;
+    raw.extend(prg);
+    let path = dir.join(name);
+    fs::write(&path, raw).unwrap();
+    path
+}
+
+#[test]
+fn test_parse_commands() {
+    assert_eq!(
+        parse_args(&args("run game.nes")),
+        Ok(Command::Run {
+            rom: PathBuf::from("game.nes"),
+            frames: DEFAULT_RUN_FRAMES,
+            stop_on_brk: false
+        })
+    );
+    assert_eq!(
+        parse_args(&args("run game.nes --frames 5 --stop-on-brk")),
+        Ok(Command::Run {
+            rom: PathBuf::from("game.nes"),
+            frames: 5,
+            stop_on_brk: true
+        })
+    );
+    assert_eq!(
+        parse_args(&args("trace game.nes --frames 3")),
+        Ok(Command::Trace {
+            rom: PathBuf::from("game.nes"),
+            frames: 3,
+            out: None
+        })
+    );
+    assert_eq!(
+        parse_args(&args("trace game.nes --out game.log")),
+        Ok(Command::Trace {
+            rom: PathBuf::from("game.nes"),
+            frames: 1,
+            out: Some(PathBuf::from("game.log"))
+        })
+    );
+    assert_eq!(
+        parse_args(&args("screenshot game.nes --frame 60 --out shot.png")),
+        Ok(Command::Screenshot {
+            rom: PathBuf::from("game.nes"),
+            frame: 60,
+            out: PathBuf::from("shot.png"),
+            options: ScreenshotOptions::default()
+        })
+    );
+    assert_eq!(
+        parse_args(&args(
+            "screenshot game.nes --out a.png --aspect --crop-overscan"
+        )),
+        Ok(Command::Screenshot {
+            rom: PathBuf::from("game.nes"),
+            frame: 1,
+            out: PathBuf::from("a.png"),
+            options: ScreenshotOptions {
+                pixel_aspect: true,
+                crop_overscan: true
+            }
+        })
+    );
+    assert_eq!(
+        parse_args(&args("video game.nes --out - --raw --frames 10")),
+        Ok(Command::Video {
+            rom: PathBuf::from("game.nes"),
+            frames: 10,
+            out: PathBuf::from("-"),
+            format: VideoFormat::RawRgb24
+        })
+    );
+    assert_eq!(
+        parse_args(&args("wav game.nes --out game.wav --split-channels")),
+        Ok(Command::Wav {
+            rom: PathBuf::from("game.nes"),
+            frames: DEFAULT_WAV_FRAMES,
+            out: PathBuf::from("game.wav"),
+            split_channels: true
+        })
+    );
+    assert_eq!(
+        parse_args(&args("terminal game.nes")),
+        Ok(Command::Terminal {
+            rom: PathBuf::from("game.nes")
+        })
+    );
+    assert_eq!(
+        parse_args(&args("window game.nes --scale 2 --pacing timer --mute")),
+        Ok(Command::Window {
+            rom: PathBuf::from("game.nes"),
+            scale: 2,
+            fullscreen: false,
+            bindings: None,
+            pacing: Pacing::Timer,
+            mute: true
+        })
+    );
+    assert_eq!(
+        parse_args(&args("window game.nes --fullscreen --bindings keys.cfg")),
+        Ok(Command::Window {
+            rom: PathBuf::from("game.nes"),
+            scale: DEFAULT_WINDOW_SCALE,
+            fullscreen: true,
+            bindings: Some(PathBuf::from("keys.cfg")),
+            pacing: Pacing::Audio,
+            mute: false
+        })
+    );
+    assert_eq!(
+        parse_args(&args("test game.nes")),
+        Ok(Command::Test {
+            rom: PathBuf::from("game.nes"),
+            frames: DEFAULT_TEST_FRAMES
+        })
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(parse_args(&[]).is_err());
+    assert!(parse_args(&args("run")).is_err());
+    assert!(parse_args(&args("fly game.nes")).is_err());
+    assert!(parse_args(&args("run game.nes --frames many")).is_err());
+    assert!(parse_args(&args("run game.nes --speed 2")).is_err());
+    assert!(parse_args(&args("screenshot game.nes")).is_err());
+    assert!(parse_args(&args("video game.nes")).is_err());
+    assert!(parse_args(&args("wav game.nes")).is_err());
+    assert!(parse_args(&args("window game.nes --pacing fast")).is_err());
+    assert!(parse_args(&args("run a.nes b.nes")).is_err());
+}
+
+#[test]
+fn test_exit_codes() {
+    assert_eq!(run(&args("run")), EXIT_USAGE);
+    assert_eq!(run(&args("info /nonexistent/game.nes")), EXIT_BAD_ROM);
+
+    let dir = TempDir::new("cli");
+    let rom = write_rom(&dir, "ok.nes", &[0xa9, 0x01, 0x00], 0);
+    let rom = rom.to_str().unwrap();
+    assert_eq!(run(&args(&format!("info {}", rom))), EXIT_SUCCESS);
+    assert_eq!(
+        run(&args(&format!("run {} --stop-on-brk", rom))),
+        EXIT_SUCCESS
+    );
+    assert_eq!(run(&args(&format!("run {} --frames 2", rom))), EXIT_SUCCESS);
+    assert_eq!(
+        run(&args(&format!("test {} --frames 2", rom))),
+        EXIT_NO_RESULT
+    );
+
+    let shot = dir.join("ok.png").display().to_string();
+    assert_eq!(
+        run(&args(&format!(
+            "screenshot {} --out {} --crop-overscan",
+            rom, shot
+        ))),
+        EXIT_SUCCESS
+    );
+    //no PPU draws into the frame yet, so this is the blank one
+    let options = ScreenshotOptions {
+        crop_overscan: true,
+        ..ScreenshotOptions::default()
+    };
+    assert_eq!(
+        fs::read(&shot).unwrap(),
+        screenshot::encode(&Frame::new(), options)
+    );
+
+    let video = dir.join("ok.y4m").display().to_string();
+    assert_eq!(
+        run(&args(&format!("video {} --out {} --frames 2", rom, video))),
+        EXIT_SUCCESS
+    );
+    assert!(fs::read(&video)
+        .unwrap()
+        .starts_with(b"YUV4MPEG2 W256 H240"));
+
+    let wav = dir.join("ok.wav").display().to_string();
+    assert_eq!(
+        run(&args(&format!(
+            "wav {} --out {} --frames 2 --split-channels",
+            rom, wav
+        ))),
+        EXIT_SUCCESS
+    );
+    assert_eq!(&fs::read(&wav).unwrap()[8..12], b"WAVE");
+    assert!(Path::new(&dir.join("ok-dmc.wav").display().to_string()).exists());
+
+    let log = dir.join("ok.log").display().to_string();
+    assert_eq!(
+        run(&args(&format!("trace {} --out {}", rom, log))),
+        EXIT_SUCCESS
+    );
+    assert!(fs::read_to_string(&log)
+        .unwrap()
+        .starts_with("8000  A9 01     LDA #$01                        A:00 X:00 Y:00 P:00 SP:FD PPU:  0,  0 CYC:0\n"));
+
+    let huge = dir.join("huge.nes").display().to_string();
+    fs::write(&huge, b"NES\x1a\xff\x00\x00\x08\x00\x0f\0\0\0\0\0\0").unwrap();
+    assert_eq!(run(&args(&format!("info {}", huge))), EXIT_BAD_ROM);
+
+    let mapper = write_rom(&dir, "mmc1.nes", &[0x00], 1);
+    let mapper = mapper.to_str().unwrap();
+    assert_eq!(run(&args(&format!("run {}", mapper))), EXIT_BAD_ROM);
+
+    //LDA #$01; JAM
+    let jam = write_rom(&dir, "jam.nes", &[0xa9, 0x01, 0x02], 0);
+    let jam = jam.to_str().unwrap();
+    assert_eq!(run(&args(&format!("run {}", jam))), EXIT_JAMMED);
+    assert_eq!(run(&args(&format!("run {} --frames 2", jam))), EXIT_JAMMED);
+}
+
+#[test]
+fn test_panics_exit_as_crashes() {
+    let mut cpu = CPU::new();
+    assert_eq!(
+        run_guarded(&mut cpu, |_| panic!("injected for the test")),
+        EXIT_CRASHED
+    );
+    assert_eq!(run_guarded(&mut cpu, |_| EXIT_NO_RESULT), EXIT_NO_RESULT);
+    cpu.jammed = true;
+    assert_eq!(run_guarded(&mut cpu, |_| EXIT_SUCCESS), EXIT_JAMMED);
+    assert_eq!(run_guarded(&mut cpu, |_| EXIT_FAILURE), EXIT_FAILURE);
+}
@@ -0,0 +1,492 @@
+use std::fs;
+use std::io::{self, BufWriter};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crate::cartridge::header::Header;
+use crate::cartridge::Rom;
+use crate::cpu::opcode_implementation::{BRK_OPCODE, CPU};
+use crate::cpu::trace::TraceLogger;
+use crate::frontend::pacer::Pacing;
+use crate::headless::audio_dump;
+use crate::headless::output;
+use crate::headless::test_rom::{self, TestResult};
+use crate::headless::video_dump::{self, VideoFormat};
+use crate::render::screenshot::{self, ScreenshotOptions};
+
+pub const EXIT_SUCCESS: i32 = 0;
+// the test ROM reported a failure
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+// the ROM couldn't be read or isn't supported
+pub const EXIT_BAD_ROM: i32 = 3;
+// the emulator hit something it can't execute
+pub const EXIT_CRASHED: i32 = 4;
+// the test ROM never reported a result
+pub const EXIT_NO_RESULT: i32 = 5;
+// the program ran into a JAM opcode and locked up the CPU
+pub const EXIT_JAMMED: i32 = 6;
+
+const DEFAULT_RUN_FRAMES: usize = 60 * 60;
+const DEFAULT_TEST_FRAMES: usize = 60 * 60;
+const DEFAULT_VIDEO_FRAMES: usize = 60;
+const DEFAULT_WAV_FRAMES: usize = 60;
+const DEFAULT_WINDOW_SCALE: usize = 3;
+
+const USAGE: &str = "usage: nesoxide <command> <rom> [options]
+
+commands:
+  run <rom> [--frames N]                  run for N frames (default 3600) or until the CPU jams
+      [--stop-on-brk]                     or until the next instruction is a BRK
+  info <rom>                              print the iNES / NES 2.0 header
+  trace <rom> [--frames N] [--out FILE]   log every instruction for N frames (default 1),
+                                          nestest.log style, to stdout or FILE
+  screenshot <rom> --out FILE [--frame N] save frame N (default 1) as PNG
+      [--aspect] [--crop-overscan]        with 8:7 pixels / without the overscan area
+  video <rom> --out FILE|- [--frames N]   write N frames (default 60) as Y4M
+      [--raw]                             as raw RGB24 instead
+  wav <rom> --out FILE [--frames N]       write N frames (default 60) of sound as 16-bit WAV
+      [--split-channels]                  plus one file per channel, out-pulse1.wav and so on
+  test <rom> [--frames N]                 run a test ROM and report its result
+  terminal <rom>                          play in the terminal (arrows, x, z, Enter, Space; q quits)
+  window <rom> [--scale N] [--fullscreen] play in a window (needs the window feature)
+      [--bindings FILE] [--pacing audio|timer] [--mute]
+
+There is no PPU yet, so screenshot and video save blank frames and terminal
+and window show a blank screen.";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        rom: PathBuf,
+        frames: usize,
+        stop_on_brk: bool,
+    },
+    Info {
+        rom: PathBuf,
+    },
+    Trace {
+        rom: PathBuf,
+        frames: usize,
+        out: Option<PathBuf>,
+    },
+    Screenshot {
+        rom: PathBuf,
+        frame: usize,
+        out: PathBuf,
+        options: ScreenshotOptions,
+    },
+    Video {
+        rom: PathBuf,
+        frames: usize,
+        out: PathBuf,
+        format: VideoFormat,
+    },
+    Wav {
+        rom: PathBuf,
+        frames: usize,
+        out: PathBuf,
+        split_channels: bool,
+    },
+    Test {
+        rom: PathBuf,
+        frames: usize,
+    },
+    Terminal {
+        rom: PathBuf,
+    },
+    Window {
+        rom: PathBuf,
+        scale: usize,
+        fullscreen: bool,
+        bindings: Option<PathBuf>,
+        pacing: Pacing,
+        mute: bool,
+    },
+}
+
+// args excludes the program name
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = args.split_first().ok_or("missing command")?;
+    let mut rom = None;
+    let mut frames = None;
+    let mut frame = None;
+    let mut out = None;
+    let mut options = ScreenshotOptions::default();
+    let mut format = VideoFormat::Y4m;
+    let mut scale = None;
+    let mut fullscreen = false;
+    let mut bindings = None;
+    let mut pacing = Pacing::Audio;
+    let mut mute = false;
+    let mut stop_on_brk = false;
+    let mut split_channels = false;
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--frames" => frames = Some(parse_count(arg, iter.next())?),
+            "--frame" => frame = Some(parse_count(arg, iter.next())?),
+            "--out" => {
+                let value = iter.next().ok_or("--out needs a file name")?;
+                out = Some(PathBuf::from(value));
+            }
+            "--aspect" => options.pixel_aspect = true,
+            "--crop-overscan" => options.crop_overscan = true,
+            "--raw" => format = VideoFormat::RawRgb24,
+            "--scale" => scale = Some(parse_count(arg, iter.next())?.max(1)),
+            "--fullscreen" => fullscreen = true,
+            "--bindings" => {
+                let value = iter.next().ok_or("--bindings needs a file name")?;
+                bindings = Some(PathBuf::from(value));
+            }
+            "--pacing" => {
+                pacing = match iter.next().map(String::as_str) {
+                    Some("audio") => Pacing::Audio,
+                    Some("timer") => Pacing::Timer,
+                    _ => return Err("--pacing is audio or timer".to_string()),
+                }
+            }
+            "--mute" => mute = true,
+            "--stop-on-brk" => stop_on_brk = true,
+            "--split-channels" => split_channels = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            path if rom.is_none() => rom = Some(PathBuf::from(path)),
+            extra => return Err(format!("unexpected argument {}", extra)),
+        }
+    }
+    let rom = rom.ok_or("missing rom path")?;
+
+    let command = match command.as_str() {
+        "run" => Command::Run {
+            rom,
+            frames: frames.unwrap_or(DEFAULT_RUN_FRAMES),
+            stop_on_brk,
+        },
+        "info" => Command::Info { rom },
+        "trace" => Command::Trace {
+            rom,
+            frames: frames.unwrap_or(1),
+            out,
+        },
+        "screenshot" => Command::Screenshot {
+            rom,
+            frame: frame.unwrap_or(1),
+            out: out.ok_or("screenshot needs --out")?,
+            options,
+        },
+        "video" => Command::Video {
+            rom,
+            frames: frames.unwrap_or(DEFAULT_VIDEO_FRAMES),
+            out: out.ok_or("video needs --out")?,
+            format,
+        },
+        "wav" => Command::Wav {
+            rom,
+            frames: frames.unwrap_or(DEFAULT_WAV_FRAMES),
+            out: out.ok_or("wav needs --out")?,
+            split_channels,
+        },
+        "test" => Command::Test {
+            rom,
+            frames: frames.unwrap_or(DEFAULT_TEST_FRAMES),
+        },
+        "terminal" => Command::Terminal { rom },
+        "window" => Command::Window {
+            rom,
+            scale: scale.unwrap_or(DEFAULT_WINDOW_SCALE),
+            fullscreen,
+            bindings,
+            pacing,
+            mute,
+        },
+        other => return Err(format!("unknown command {}", other)),
+    };
+    Ok(command)
+}
+
+fn parse_count(flag: &str, value: Option<&String>) -> Result<usize, String> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or(format!("{} needs a number", flag))
+}
+
+// Entry point for the binary. Returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let command = match parse_args(args) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("nesoxide: {}\n\n{}", message, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    let rom_path = match &command {
+        Command::Run { rom, .. }
+        | Command::Info { rom }
+        | Command::Trace { rom, .. }
+        | Command::Screenshot { rom, .. }
+        | Command::Video { rom, .. }
+        | Command::Wav { rom, .. }
+        | Command::Test { rom, .. }
+        | Command::Terminal { rom }
+        | Command::Window { rom, .. } => rom,
+    };
+    let mut cpu = CPU::new();
+    run_guarded(&mut cpu, |cpu| load_and_execute(&command, rom_path, cpu))
+}
+
+fn load_and_execute(command: &Command, rom_path: &Path, cpu: &mut CPU) -> i32 {
+    let rom = match load_rom(rom_path) {
+        Ok(rom) => rom,
+        Err(message) => {
+            eprintln!("nesoxide: {}: {}", rom_path.display(), message);
+            return EXIT_BAD_ROM;
+        }
+    };
+    if let Command::Info { .. } = command {
+        print_header(&rom.header);
+        return EXIT_SUCCESS;
+    }
+
+    if let Err(message) = cpu.load_rom(&rom) {
+        eprintln!("nesoxide: {}: {}", rom_path.display(), message);
+        return EXIT_BAD_ROM;
+    }
+    cpu.reset();
+    execute(command, cpu)
+}
+
+// Reports emulator panics as a crash rather than a backtrace, and a jammed
+// CPU as such when the command itself went fine.
+fn run_guarded(cpu: &mut CPU, execute: impl FnOnce(&mut CPU) -> i32) -> i32 {
+    match panic::catch_unwind(AssertUnwindSafe(|| execute(cpu))) {
+        Ok(code) if cpu.jammed => {
+            eprintln!("nesoxide: CPU jammed at ${:04X}", cpu.program_counter);
+            if code == EXIT_SUCCESS {
+                EXIT_JAMMED
+            } else {
+                code
+            }
+        }
+        Ok(code) => code,
+        Err(_) => {
+            eprintln!(
+                "nesoxide: emulation crashed at ${:04X}",
+                cpu.program_counter
+            );
+            EXIT_CRASHED
+        }
+    }
+}
+
+fn load_rom(path: &Path) -> Result<Rom, String> {
+    let raw = fs::read(path).map_err(|err| err.to_string())?;
+    Rom::new(&raw)
+}
+
+fn execute(command: &Command, cpu: &mut CPU) -> i32 {
+    match command {
+        Command::Run {
+            frames,
+            stop_on_brk,
+            ..
+        } => {
+            let end = frame_end(cpu, *frames);
+            while cpu.cycles < end
+                && !(*stop_on_brk && cpu.peek(cpu.program_counter) == BRK_OPCODE)
+                && cpu.step()
+            {}
+            EXIT_SUCCESS
+        }
+        Command::Trace { frames, out, .. } => match trace(cpu, *frames, out.as_deref()) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(err) => {
+                eprintln!("nesoxide: {}", err);
+                EXIT_FAILURE
+            }
+        },
+        Command::Screenshot {
+            frame,
+            out,
+            options,
+            ..
+        } => {
+            warn_blank_frames();
+            cpu.run_until(frame_end(cpu, *frame));
+            match screenshot::save_png(&cpu.frame, out, *options) {
+                Ok(()) => EXIT_SUCCESS,
+                Err(err) => {
+                    eprintln!("nesoxide: {}: {}", out.display(), err);
+                    EXIT_FAILURE
+                }
+            }
+        }
+        Command::Video {
+            frames,
+            out,
+            format,
+            ..
+        } => {
+            warn_blank_frames();
+            let result = output::open_output(out)
+                .and_then(|writer| video_dump::dump_video(cpu, *frames, writer, *format));
+            match result {
+                Ok(_) => EXIT_SUCCESS,
+                Err(err) => {
+                    eprintln!("nesoxide: {}: {}", out.display(), err);
+                    EXIT_FAILURE
+                }
+            }
+        }
+        Command::Wav {
+            frames,
+            out,
+            split_channels,
+            ..
+        } => match audio_dump::dump_audio(cpu, *frames, out, *split_channels) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(err) => {
+                eprintln!("nesoxide: {}: {}", out.display(), err);
+                EXIT_FAILURE
+            }
+        },
+        Command::Test { frames, .. } => {
+            let report = test_rom::run_test_rom(cpu, *frames);
+            print!("{}", report.text);
+            if !report.text.is_empty() && !report.text.ends_with('\n') {
+                println!();
+            }
+            match report.result {
+                TestResult::Passed => {
+                    println!("passed");
+                    EXIT_SUCCESS
+                }
+                TestResult::Failed(code) => {
+                    println!("failed with code {}", code);
+                    EXIT_FAILURE
+                }
+                TestResult::NoResult => {
+                    println!("no result");
+                    EXIT_NO_RESULT
+                }
+            }
+        }
+        Command::Terminal { .. } => {
+            warn_blank_frames();
+            match play_in_terminal(cpu) {
+                Ok(()) => EXIT_SUCCESS,
+                Err(err) => {
+                    eprintln!("nesoxide: terminal: {}", err);
+                    EXIT_FAILURE
+                }
+            }
+        }
+        Command::Window { .. } => {
+            warn_blank_frames();
+            match play_in_window(command, cpu) {
+                Ok(()) => EXIT_SUCCESS,
+                Err(err) => {
+                    eprintln!("nesoxide: window: {}", err);
+                    EXIT_FAILURE
+                }
+            }
+        }
+        Command::Info { .. } => unreachable!("info doesn't run the rom"),
+    }
+}
+
+#[cfg(unix)]
+fn play_in_terminal(cpu: &mut CPU) -> io::Result<()> {
+    crate::frontend::terminal::run(cpu)
+}
+
+#[cfg(not(unix))]
+fn play_in_terminal(_cpu: &mut CPU) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the terminal frontend needs a unix terminal",
+    ))
+}
+
+#[cfg(feature = "window")]
+fn play_in_window(command: &Command, cpu: &mut CPU) -> Result<(), String> {
+    use crate::frontend::bindings::Bindings;
+    use crate::frontend::window::{self, WindowSettings};
+
+    let Command::Window {
+        scale,
+        fullscreen,
+        bindings,
+        pacing,
+        mute,
+        ..
+    } = command
+    else {
+        unreachable!("not a window command")
+    };
+    let bindings = match bindings {
+        Some(path) => Bindings::load(path)?,
+        None => Bindings::default(),
+    };
+    window::run(
+        cpu,
+        WindowSettings {
+            scale: *scale,
+            fullscreen: *fullscreen,
+            bindings,
+            pacing: *pacing,
+            audio: !mute,
+        },
+    )
+}
+
+#[cfg(not(feature = "window"))]
+fn play_in_window(_command: &Command, _cpu: &mut CPU) -> Result<(), String> {
+    Err("nesoxide was built without the window feature".to_string())
+}
+
+// Nothing draws into `cpu.frame` until there is a PPU.
+fn warn_blank_frames() {
+    eprintln!("nesoxide: warning: there is no PPU yet, every frame is blank");
+}
+
+// The cycle count `frames` video frames from now.
+fn frame_end(cpu: &CPU, frames: usize) -> usize {
+    cpu.cycles + (frames as f64 * cpu.apu.region().cpu_cycles_per_frame()) as usize
+}
+
+// One nestest.log style line per instruction, to `out` or stdout.
+fn trace(cpu: &mut CPU, frames: usize, out: Option<&Path>) -> io::Result<()> {
+    let end = frame_end(cpu, frames);
+    let output = match out {
+        Some(path) => output::open_output(path)?,
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    cpu.trace = Some(TraceLogger::new(output));
+    cpu.run_until(end);
+    cpu.trace.take().map_or(Ok(()), TraceLogger::finish)
+}
+
+pub fn print_header(header: &Header) {
+    println!(
+        "format:          {}",
+        if header.nes2 { "NES 2.0" } else { "iNES" }
+    );
+    println!("mapper:          {}", header.mapper);
+    println!("submapper:       {}", header.submapper);
+    println!("PRG ROM:         {} KiB", header.prg_rom_size / 1024);
+    println!("CHR ROM:         {} KiB", header.chr_rom_size / 1024);
+    println!("PRG RAM:         {} bytes", header.prg_ram_size);
+    println!("PRG NVRAM:       {} bytes", header.prg_nvram_size);
+    println!("CHR RAM:         {} bytes", header.chr_ram_size);
+    println!("CHR NVRAM:       {} bytes", header.chr_nvram_size);
+    println!("mirroring:       {:?}", header.mirroring);
+    println!("battery:         {}", header.battery);
+    println!("trainer:         {}", header.trainer);
+    println!("console type:    {}", header.console_type);
+    println!("timing:          {:?}", header.timing);
+    println!("expansion:       ${:02X}", header.default_expansion_device);
+}
+
+#[cfg(test)]
+#[path = "./cli_test.rs"]
+mod cli_tests;
@@ -0,0 +1,32 @@
+// One CPU bus cycle. With `CPU::bus_log` set every read and write the CPU
+// makes is recorded in order, which is what the per-cycle tests compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusOperation {
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusAccess {
+    pub addr: u16,
+    pub value: u8,
+    pub operation: BusOperation,
+}
+
+impl BusAccess {
+    pub fn read(addr: u16, value: u8) -> Self {
+        Self {
+            addr,
+            value,
+            operation: BusOperation::Read,
+        }
+    }
+
+    pub fn write(addr: u16, value: u8) -> Self {
+        Self {
+            addr,
+            value,
+            operation: BusOperation::Write,
+        }
+    }
+}
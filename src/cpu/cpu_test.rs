@@ -1,4 +1,6 @@
 use super::*;
+use crate::cartridge::Rom;
+use crate::input::ExpansionDevice;
 #[test]
 fn test_5_ops_working_together() {
     let mut cpu = CPU::new();
@@ -48,17 +50,23 @@ fn test_0xa9_lda_load_data() {
     assert!(cpu.status_register & NEGETIVE == 0b00);
     assert_eq!(cpu.register_a, 0x05);
     //Lda AbsoluteX opcode
-    cpu.run_program(vec![0xBD, 0x00, 0x85, 0x00]);
-    cpu.memory[0xaa] = 0x05;
+    cpu.memory[0x8504] = 0x06;
+    cpu.load_program(vec![0xBD, 0x00, 0x85, 0x00]);
+    cpu.reset();
+    cpu.register_x = 0x04;
+    cpu.interpret();
     assert!(cpu.status_register & ZERO == 0b00);
     assert!(cpu.status_register & NEGETIVE == 0b00);
-    assert_eq!(cpu.register_a, 0x05);
+    assert_eq!(cpu.register_a, 0x06);
     //Lda AbsoluteY opcode
-    cpu.run_program(vec![0xB9, 0x00, 0x85, 0x00]);
-    cpu.memory[0x8500] = 0x05;
+    cpu.memory[0x8508] = 0x07;
+    cpu.load_program(vec![0xB9, 0x00, 0x85, 0x00]);
+    cpu.reset();
+    cpu.register_y = 0x08;
+    cpu.interpret();
     assert!(cpu.status_register & ZERO == 0b00);
     assert!(cpu.status_register & NEGETIVE == 0b00);
-    assert_eq!(cpu.register_a, 0x05);
+    assert_eq!(cpu.register_a, 0x07);
     //Lda IndirectX opcode
     cpu.memory[0xa9] = 0x00;
     cpu.memory[0xaa] = 0x85;
@@ -352,3 +360,750 @@ fn test_0xaa_tax_negetive_flag() {
 
     assert!(cpu.status_register & NEGETIVE == NEGETIVE);
 }
+
+#[test]
+fn test_apu_register_writes_are_routed() {
+    let mut cpu = CPU::new();
+    cpu.mem_write(0x4002, 0x34);
+    cpu.mem_write(0x4003, 0x02);
+    assert_eq!(cpu.apu.pulse1.timer_period(), 0x234);
+    assert_eq!(cpu.memory[0x4002], 0x00);
+    cpu.mem_write(0x4006, 0x12);
+    assert_eq!(cpu.apu.pulse2.timer_period(), 0x12);
+}
+
+#[test]
+fn test_dmc_irq_is_serviced() {
+    let mut cpu = CPU::new();
+    //IRQ vector
+    cpu.mem_write_u16(0xFFFE, 0x9000);
+    cpu.memory[0x9000] = 0xa9;
+    cpu.memory[0x9001] = 0x42;
+    cpu.memory[0x9002] = 0x00;
+    cpu.load_program(vec![0xe8, 0xe8, 0xe8, 0x00]);
+    cpu.reset();
+    //single byte sample with the irq enabled
+    cpu.mem_write(0x4010, 0x80);
+    cpu.apu.dmc.set_enabled(true);
+    cpu.interpret();
+    assert_eq!(cpu.register_a, 0x42);
+    assert_eq!(cpu.register_x, 1);
+    //the fetch stalled the CPU on top of INX and the interrupt sequence
+    assert_eq!(cpu.cycles, 2 + 4 + 7 + 2);
+    //return address and flags were pushed
+    assert_eq!(cpu.stack_pointer, 0xFA);
+    assert_eq!(cpu.memory[0x01FD], 0x80);
+    assert_eq!(cpu.memory[0x01FC], 0x01);
+    assert_eq!(cpu.memory[0x01FB] & BREAK, 0);
+    assert!(cpu.status_register & INTERRUPT == INTERRUPT);
+}
+
+#[test]
+fn test_0x40_rti_returns_from_interrupt() {
+    let mut cpu = CPU::new();
+    cpu.mem_write_u16(0xFFFE, 0x9000);
+    //handler acknowledges the DMC by rewriting $4010 with ASL and returns
+    cpu.memory[0x9000] = 0x0e;
+    cpu.memory[0x9001] = 0x10;
+    cpu.memory[0x9002] = 0x40;
+    cpu.memory[0x9003] = 0x40;
+    cpu.load_program(vec![0xe8, 0xe8, 0x00]);
+    cpu.reset();
+    cpu.mem_write(0x4010, 0x80);
+    cpu.apu.dmc.set_enabled(true);
+    cpu.interpret();
+    assert!(!cpu.apu.irq());
+    assert_eq!(cpu.register_x, 2);
+    assert_eq!(cpu.program_counter, 0x8002);
+    assert_eq!(cpu.stack_pointer, STACK_RESET);
+}
+
+#[test]
+fn test_apu_status_read_is_routed() {
+    let mut cpu = CPU::new();
+    cpu.mem_write(0x4015, 0b0000_0001);
+    cpu.mem_write(0x4003, 0b0000_1000);
+    cpu.run_program(vec![0xad, 0x15, 0x40, 0x00]);
+    assert_eq!(cpu.register_a, 0b0000_0001);
+}
+
+#[test]
+fn test_controller_reads_are_routed() {
+    let mut cpu = CPU::new();
+    cpu.input
+        .controller(1)
+        .unwrap()
+        .set_buttons(crate::input::controller::BUTTON_A);
+    cpu.mem_write(0x4016, 1);
+    cpu.mem_write(0x4016, 0);
+    cpu.run_program(vec![0xad, 0x16, 0x40, 0x00]);
+    assert_eq!(cpu.register_a, 0x41);
+    cpu.run_program(vec![0xad, 0x16, 0x40, 0x00]);
+    assert_eq!(cpu.register_a, 0x40);
+    assert_eq!(cpu.memory[0x4016], 0);
+}
+
+#[test]
+fn test_zapper_reads_sense_the_beam_position() {
+    let mut cpu = CPU::new();
+    cpu.input = InputPorts::with_device(ExpansionDevice::Zapper);
+    cpu.input.zapper(2).unwrap().set_position(Some((100, 100)));
+    for y in 96..104 {
+        for x in 96..104 {
+            cpu.frame.set_pixel(x, y, (0xFF, 0xFF, 0xFF));
+        }
+    }
+    //341 dots a scanline, three per CPU cycle
+    cpu.cycles = 90 * 341 / 3;
+    assert_eq!(cpu.mem_read(0x4017) & 0b0000_1000, 0b0000_1000);
+    cpu.cycles = 105 * 341 / 3;
+    assert_eq!(cpu.mem_read(0x4017) & 0b0000_1000, 0);
+}
+
+fn nrom(prg_banks: u8, program: &[u8]) -> Rom {
+    let mut raw = vec![b'N', b'E', b'S', 0x1A, prg_banks, 0, 0, 0];
+    raw.resize(16, 0);
+    let mut prg = vec![0; prg_banks as usize * 0x4000];
+    prg[..program.len()].copy_from_slice(program);
+    //reset vector at the end of the image points to its start
+    let len = prg.len();
+    prg[len - 4] = 0x00;
+    prg[len - 3] = 0x80;
+    raw.extend(prg);
+    Rom::new(&raw).unwrap()
+}
+
+#[test]
+fn test_load_rom_mirrors_16k_prg() {
+    let mut cpu = CPU::new();
+    cpu.load_rom(&nrom(1, &[0xa9, 0x42, 0x00])).unwrap();
+    cpu.reset();
+    assert_eq!(cpu.program_counter, 0x8000);
+    assert_eq!(cpu.mem_read(0xC001), 0x42);
+    cpu.interpret();
+    assert_eq!(cpu.register_a, 0x42);
+}
+
+#[test]
+fn test_load_rom_rejects_other_mappers() {
+    let mut rom = nrom(2, &[0x00]);
+    assert!(CPU::new().load_rom(&rom).is_ok());
+    rom.header.mapper = 1;
+    assert!(CPU::new().load_rom(&rom).is_err());
+}
+
+#[test]
+fn test_adc_sbc_carry_and_overflow() {
+    let mut cpu = CPU::new();
+    //0x50 + 0x50 overflows into the sign bit
+    cpu.run_program(vec![0xa9, 0x50, 0x69, 0x50, 0x00]);
+    assert_eq!(cpu.register_a, 0xa0);
+    assert_eq!(cpu.status_register & (CARRY | OVERFLOW), OVERFLOW);
+    //0xff + 0x01 carries out
+    cpu.run_program(vec![0xa9, 0xff, 0x69, 0x01, 0x00]);
+    assert_eq!(cpu.register_a, 0x00);
+    assert_eq!(
+        cpu.status_register & (CARRY | ZERO | OVERFLOW),
+        CARRY | ZERO
+    );
+    //SEC; 0x05 - 0x06 borrows
+    cpu.run_program(vec![0x38, 0xa9, 0x05, 0xe9, 0x06, 0x00]);
+    assert_eq!(cpu.register_a, 0xff);
+    assert_eq!(cpu.status_register & (CARRY | NEGETIVE), NEGETIVE);
+}
+
+#[test]
+fn test_compare_sets_carry_zero_negetive() {
+    let mut cpu = CPU::new();
+    cpu.run_program(vec![0xa9, 0x40, 0xc9, 0x40, 0x00]);
+    assert_eq!(
+        cpu.status_register & (CARRY | ZERO | NEGETIVE),
+        CARRY | ZERO
+    );
+    cpu.run_program(vec![0xa2, 0x10, 0xe0, 0x20, 0x00]);
+    assert_eq!(cpu.status_register & (CARRY | ZERO | NEGETIVE), NEGETIVE);
+}
+
+#[test]
+fn test_shifts_and_rotates_through_carry() {
+    let mut cpu = CPU::new();
+    //SEC; LDA #$81; ROR A
+    cpu.run_program(vec![0x38, 0xa9, 0x81, 0x6a, 0x00]);
+    assert_eq!(cpu.register_a, 0xc0);
+    assert_eq!(cpu.status_register & CARRY, CARRY);
+    //ROL $aa then LSR $aa
+    let addr = set_zeropage_value(&mut cpu, 0x81);
+    cpu.run_program(vec![0x26, addr, 0x46, addr, 0x00]);
+    assert_eq!(cpu.mem_read(addr as u16), 0x01);
+    assert_eq!(cpu.status_register & CARRY, 0);
+}
+
+#[test]
+fn test_jsr_rts_and_stack() {
+    let mut cpu = CPU::new();
+    //JSR $8006; LDX #$07; BRK; LDA #$05; PHA; PLA... RTS
+    cpu.run_program(vec![
+        0x20, 0x06, 0x80, 0xa2, 0x07, 0x00, 0xa9, 0x05, 0x48, 0xa9, 0x00, 0x68, 0x60,
+    ]);
+    assert_eq!(cpu.register_a, 0x05);
+    assert_eq!(cpu.register_x, 0x07);
+    assert_eq!(cpu.stack_pointer, 0xfd);
+}
+
+#[test]
+fn test_php_plp_b_flags() {
+    let mut cpu = CPU::new();
+    //SEC; PHP; PLA
+    cpu.run_program(vec![0x38, 0x08, 0x68, 0x00]);
+    assert_eq!(cpu.register_a, CARRY | BREAK | BREAK2);
+    //LDA #$ff; PHA; PLP
+    cpu.run_program(vec![0xa9, 0xff, 0x48, 0x28, 0x00]);
+    assert_eq!(cpu.status_register, !BREAK);
+}
+
+#[test]
+fn test_jmp_and_memory_increments() {
+    let mut cpu = CPU::new();
+    //JMP $8005; BRK; BRK; INC $aa; LDX $aa; INX
+    let addr = set_zeropage_value(&mut cpu, 0x7f);
+    cpu.run_program(vec![
+        0x4c, 0x05, 0x80, 0x00, 0x00, 0xe6, addr, 0xa6, addr, 0x00,
+    ]);
+    assert_eq!(cpu.register_x, 0x80);
+    assert_eq!(cpu.status_register & NEGETIVE, NEGETIVE);
+}
+
+#[test]
+fn test_decimal_mode_only_on_generic_6502() {
+    //SED; CLC; LDA #$19; ADC #$28
+    let program = vec![0xf8, 0x18, 0xa9, 0x19, 0x69, 0x28, 0x00];
+    let mut cpu = CPU::new();
+    cpu.run_program(program.clone());
+    assert_eq!(cpu.register_a, 0x41);
+    cpu.machine = Machine::Generic6502;
+    cpu.run_program(program);
+    assert_eq!(cpu.register_a, 0x47);
+    //SED; CLC; LDA #$58; ADC #$46 carries out
+    cpu.run_program(vec![0xf8, 0x18, 0xa9, 0x58, 0x69, 0x46, 0x00]);
+    assert_eq!(cpu.register_a, 0x04);
+    assert_eq!(cpu.status_register & CARRY, CARRY);
+    //SED; SEC; LDA #$40; SBC #$13
+    cpu.run_program(vec![0xf8, 0x38, 0xa9, 0x40, 0xe9, 0x13, 0x00]);
+    assert_eq!(cpu.register_a, 0x27);
+    assert_eq!(cpu.status_register & CARRY, CARRY);
+    //SED; SEC; LDA #$12; SBC #$21 borrows
+    cpu.run_program(vec![0xf8, 0x38, 0xa9, 0x12, 0xe9, 0x21, 0x00]);
+    assert_eq!(cpu.register_a, 0x91);
+    assert_eq!(cpu.status_register & CARRY, 0);
+}
+
+#[test]
+fn test_generic_6502_memory_is_flat() {
+    let mut cpu = CPU::new();
+    cpu.machine = Machine::Generic6502;
+    cpu.mem_write(0x4015, 0x1f);
+    cpu.mem_write(0x4016, 0x01);
+    assert_eq!(cpu.mem_read(0x4015), 0x1f);
+    assert_eq!(cpu.mem_read(0x4016), 0x01);
+    cpu.load_image(&[0xa9, 0x33, 0x00], 0xfff0).unwrap();
+    cpu.program_counter = 0xfff0;
+    cpu.interpret();
+    assert_eq!(cpu.register_a, 0x33);
+}
+
+#[test]
+fn test_unofficial_load_store_and_rmw() {
+    let mut cpu = CPU::new();
+    //LAX $aa
+    let addr = set_zeropage_value(&mut cpu, 0x81);
+    cpu.run_program(vec![0xa7, addr, 0x00]);
+    assert_eq!((cpu.register_a, cpu.register_x), (0x81, 0x81));
+    assert_eq!(cpu.status_register & NEGETIVE, NEGETIVE);
+    //LDA #$f0; LDX #$3c; SAX $aa
+    cpu.run_program(vec![0xa9, 0xf0, 0xa2, 0x3c, 0x87, addr, 0x00]);
+    assert_eq!(cpu.mem_read(addr as u16), 0x30);
+    //LDA #$2f; DCP $aa leaves $2f there, equal to A
+    cpu.run_program(vec![0xa9, 0x2f, 0xc7, addr, 0x00]);
+    assert_eq!(cpu.mem_read(addr as u16), 0x2f);
+    assert_eq!(cpu.status_register & (CARRY | ZERO), CARRY | ZERO);
+    //SEC; LDA #$40; ISB $aa
+    cpu.run_program(vec![0x38, 0xa9, 0x40, 0xe7, addr, 0x00]);
+    assert_eq!(cpu.mem_read(addr as u16), 0x30);
+    assert_eq!(cpu.register_a, 0x10);
+    //LDA #$01; SLO $aa
+    cpu.run_program(vec![0xa9, 0x01, 0x07, addr, 0x00]);
+    assert_eq!(cpu.mem_read(addr as u16), 0x60);
+    assert_eq!(cpu.register_a, 0x61);
+    //LDA #$10; RRA $aa adds $30 and the carry ROR shifted out
+    set_zeropage_value(&mut cpu, 0x61);
+    cpu.run_program(vec![0xa9, 0x10, 0x67, addr, 0x00]);
+    assert_eq!(cpu.mem_read(addr as u16), 0x30);
+    assert_eq!(cpu.register_a, 0x41);
+}
+
+#[test]
+fn test_unofficial_immediates() {
+    let mut cpu = CPU::new();
+    //LDA #$c3; ANC #$81
+    cpu.run_program(vec![0xa9, 0xc3, 0x0b, 0x81, 0x00]);
+    assert_eq!(cpu.register_a, 0x81);
+    assert_eq!(cpu.status_register & (CARRY | NEGETIVE), CARRY | NEGETIVE);
+    //LDA #$ff; ALR #$03
+    cpu.run_program(vec![0xa9, 0xff, 0x4b, 0x03, 0x00]);
+    assert_eq!(cpu.register_a, 0x01);
+    assert_eq!(cpu.status_register & CARRY, CARRY);
+    //SEC; LDA #$ff; ARR #$c0
+    cpu.run_program(vec![0x38, 0xa9, 0xff, 0x6b, 0xc0, 0x00]);
+    assert_eq!(cpu.register_a, 0xe0);
+    assert_eq!(cpu.status_register & (CARRY | OVERFLOW), CARRY);
+    //LDA #$0f; LDX #$fc; SBX #$02
+    cpu.run_program(vec![0xa9, 0x0f, 0xa2, 0xfc, 0xcb, 0x02, 0x00]);
+    assert_eq!(cpu.register_x, 0x0a);
+    assert_eq!(cpu.status_register & CARRY, CARRY);
+}
+
+#[test]
+fn test_unstable_opcodes_use_magic() {
+    let mut cpu = CPU::new();
+    //LDA #$01; LDX #$ff; ANE #$ff
+    let program = vec![0xa9, 0x01, 0xa2, 0xff, 0x8b, 0xff, 0x00];
+    cpu.run_program(program.clone());
+    assert_eq!(cpu.register_a, 0xef);
+    cpu.magic = 0xff;
+    cpu.run_program(program);
+    assert_eq!(cpu.register_a, 0xff);
+    //LDA #$00; LXA #$0f
+    cpu.magic = 0x00;
+    cpu.run_program(vec![0xa9, 0x00, 0xab, 0x0f, 0x00]);
+    assert_eq!((cpu.register_a, cpu.register_x), (0x00, 0x00));
+    assert_eq!(cpu.status_register & ZERO, ZERO);
+}
+
+#[test]
+fn test_unstable_stores_and_high_byte() {
+    let mut cpu = CPU::new();
+    //LDX #$ff; LDY #$01; SHX $0200,Y stores X AND $03
+    cpu.run_program(vec![0xa2, 0xff, 0xa0, 0x01, 0x9e, 0x00, 0x02, 0x00]);
+    assert_eq!(cpu.mem_read(0x0201), 0x03);
+    //crossing a page the value replaces the address high byte
+    //LDX #$01; LDY #$02; SHX $02ff,Y stores $01 at $0101, not $0301
+    cpu.run_program(vec![0xa2, 0x01, 0xa0, 0x02, 0x9e, 0xff, 0x02, 0x00]);
+    assert_eq!(cpu.mem_read(0x0101), 0x01);
+    assert_eq!(cpu.mem_read(0x0301), 0x00);
+    //LDA #$ff; LDX #$0f; LDY #$00; TAS $0100,Y
+    cpu.run_program(vec![
+        0xa9, 0xff, 0xa2, 0x0f, 0xa0, 0x00, 0x9b, 0x00, 0x01, 0x00,
+    ]);
+    assert_eq!(cpu.stack_pointer, 0x0f);
+    assert_eq!(cpu.mem_read(0x0100), 0x02);
+}
+
+#[test]
+fn test_unofficial_nops_read_their_operand() {
+    let mut cpu = CPU::new();
+    cpu.load_program(vec![0x1a, 0x0c, 0x34, 0x12, 0x80, 0x55, 0x00]);
+    cpu.reset();
+    cpu.bus_log = Some(Vec::new());
+    cpu.interpret();
+    let reads: Vec<u16> = cpu
+        .bus_log
+        .unwrap()
+        .iter()
+        .map(|access| access.addr)
+        .collect();
+    assert!(reads.contains(&0x1234));
+    assert_eq!(cpu.program_counter, 0x8006);
+}
+
+#[test]
+fn test_jam_halts_until_reset() {
+    let mut cpu = CPU::new();
+    //LDA #$01; JAM; LDA #$02
+    cpu.load_program(vec![0xa9, 0x01, 0x12, 0xa9, 0x02, 0x00]);
+    cpu.reset();
+    assert!(!cpu.run_until(1000));
+    assert!(cpu.jammed);
+    assert_eq!(cpu.program_counter, 0x8002);
+    assert_eq!(cpu.register_a, 0x01);
+    //the rest of the system keeps being clocked
+    assert!(cpu.cycles >= 1000);
+    assert!(!cpu.step());
+    assert_eq!(cpu.program_counter, 0x8002);
+
+    cpu.soft_reset();
+    assert!(!cpu.jammed);
+    assert!(cpu.step());
+    assert_eq!(cpu.program_counter, 0x8002);
+}
+
+// Runs one instruction and returns every bus access it made.
+fn step_logged(cpu: &mut CPU) -> Vec<BusAccess> {
+    cpu.bus_log = Some(Vec::new());
+    cpu.step();
+    cpu.bus_log.take().unwrap()
+}
+
+#[test]
+fn test_rmw_writes_twice() {
+    let mut cpu = CPU::new();
+    cpu.load_program(vec![0xee, 0x10, 0x02, 0x00]);
+    cpu.reset();
+    cpu.mem_write(0x0210, 0x41);
+    let start = cpu.cycles;
+    assert_eq!(
+        step_logged(&mut cpu),
+        [
+            BusAccess::read(0x8000, 0xee),
+            BusAccess::read(0x8001, 0x10),
+            BusAccess::read(0x8002, 0x02),
+            BusAccess::read(0x0210, 0x41),
+            BusAccess::write(0x0210, 0x41),
+            BusAccess::write(0x0210, 0x42),
+        ]
+    );
+    assert_eq!(cpu.cycles - start, 6);
+}
+
+#[test]
+fn test_indexed_read_page_cross() {
+    let mut cpu = CPU::new();
+    cpu.load_program(vec![0xbd, 0xf0, 0x02, 0xbd, 0xf0, 0x02, 0x00]);
+    cpu.reset();
+    cpu.mem_write(0x0210, 0x11);
+    cpu.mem_write(0x0310, 0x22);
+    cpu.mem_write(0x02f1, 0x33);
+
+    // crossing into $03xx first reads the address before the carry
+    cpu.register_x = 0x20;
+    let start = cpu.cycles;
+    assert_eq!(
+        step_logged(&mut cpu)[3..],
+        [BusAccess::read(0x0210, 0x11), BusAccess::read(0x0310, 0x22)]
+    );
+    assert_eq!(cpu.cycles - start, 5);
+    assert_eq!(cpu.register_a, 0x22);
+
+    cpu.register_x = 0x01;
+    let start = cpu.cycles;
+    assert_eq!(step_logged(&mut cpu)[3..], [BusAccess::read(0x02f1, 0x33)]);
+    assert_eq!(cpu.cycles - start, 4);
+}
+
+#[test]
+fn test_indexed_store_always_reads_first() {
+    let mut cpu = CPU::new();
+    cpu.load_program(vec![0x9d, 0x00, 0x02, 0x00]);
+    cpu.reset();
+    cpu.register_a = 0x99;
+    cpu.register_x = 0x01;
+    let start = cpu.cycles;
+    assert_eq!(
+        step_logged(&mut cpu)[3..],
+        [
+            BusAccess::read(0x0201, 0x00),
+            BusAccess::write(0x0201, 0x99)
+        ]
+    );
+    assert_eq!(cpu.cycles - start, 5);
+}
+
+#[test]
+fn test_jsr_rts_bus_order() {
+    let mut cpu = CPU::new();
+    //JSR $8004; BRK; NOP; RTS
+    cpu.load_program(vec![0x20, 0x04, 0x80, 0x00, 0x60, 0x00]);
+    cpu.reset();
+    assert_eq!(
+        step_logged(&mut cpu),
+        [
+            BusAccess::read(0x8000, 0x20),
+            BusAccess::read(0x8001, 0x04),
+            BusAccess::read(0x01fd, 0x00),
+            BusAccess::write(0x01fd, 0x80),
+            BusAccess::write(0x01fc, 0x02),
+            BusAccess::read(0x8002, 0x80),
+        ]
+    );
+    assert_eq!(
+        step_logged(&mut cpu),
+        [
+            BusAccess::read(0x8004, 0x60),
+            BusAccess::read(0x8005, 0x00),
+            BusAccess::read(0x01fb, 0x00),
+            BusAccess::read(0x01fc, 0x02),
+            BusAccess::read(0x01fd, 0x80),
+            BusAccess::read(0x8002, 0x80),
+        ]
+    );
+    assert_eq!(cpu.program_counter, 0x8003);
+}
+
+#[test]
+fn test_single_byte_instructions_read_the_next_byte() {
+    let mut cpu = CPU::new();
+    //INX; PLA
+    cpu.load_program(vec![0xe8, 0x68, 0x00]);
+    cpu.reset();
+    assert_eq!(
+        step_logged(&mut cpu),
+        [BusAccess::read(0x8000, 0xe8), BusAccess::read(0x8001, 0x68)]
+    );
+    assert_eq!(
+        step_logged(&mut cpu),
+        [
+            BusAccess::read(0x8001, 0x68),
+            BusAccess::read(0x8002, 0x00),
+            BusAccess::read(0x01fd, 0x00),
+            BusAccess::read(0x01fe, 0x00),
+        ]
+    );
+}
+
+#[test]
+fn test_indirect_x_pointer_wraps_in_zero_page() {
+    let mut cpu = CPU::new();
+    //LDA ($FF,X); LDA ($80,X)
+    cpu.load_program(vec![0xa1, 0xff, 0xa1, 0x80, 0x00]);
+    cpu.reset();
+    cpu.mem_write(0xff, 0x34);
+    cpu.mem_write(0x00, 0x02);
+    cpu.mem_write(0x0100, 0x12);
+    cpu.mem_write(0x0234, 0x55);
+    cpu.step();
+    assert_eq!(cpu.register_a, 0x55);
+
+    // $80 + $7F stays at $FF rather than reaching $0100
+    cpu.register_x = 0x7f;
+    cpu.mem_write(0x0234, 0x66);
+    cpu.step();
+    assert_eq!(cpu.register_a, 0x66);
+}
+
+#[test]
+fn test_indirect_y_pointer_wraps_in_zero_page() {
+    let mut cpu = CPU::new();
+    //LDA ($FF),Y
+    cpu.load_program(vec![0xb1, 0xff, 0x00]);
+    cpu.reset();
+    cpu.mem_write(0xff, 0x30);
+    cpu.mem_write(0x00, 0x02);
+    cpu.mem_write(0x0100, 0x12);
+    cpu.mem_write(0x0235, 0x77);
+    cpu.register_y = 0x05;
+    cpu.step();
+    assert_eq!(cpu.register_a, 0x77);
+}
+
+#[test]
+fn test_indirect_y_indexes_the_address_not_the_pointer() {
+    let mut cpu = CPU::new();
+    //LDA ($10),Y; STA ($10),Y
+    cpu.load_program(vec![0xb1, 0x10, 0x91, 0x10, 0x00]);
+    cpu.reset();
+    cpu.mem_write(0x10, 0xf0);
+    cpu.mem_write(0x11, 0x02);
+    cpu.mem_write(0x12, 0x04);
+    cpu.mem_write(0x0210, 0x11);
+    cpu.mem_write(0x0310, 0x22);
+    cpu.register_y = 0x20;
+
+    // crossing a page reads before the carry and costs a cycle
+    let start = cpu.cycles;
+    assert_eq!(
+        step_logged(&mut cpu)[2..],
+        [
+            BusAccess::read(0x10, 0xf0),
+            BusAccess::read(0x11, 0x02),
+            BusAccess::read(0x0210, 0x11),
+            BusAccess::read(0x0310, 0x22),
+        ]
+    );
+    assert_eq!(cpu.cycles - start, 6);
+    assert_eq!(cpu.register_a, 0x22);
+
+    cpu.register_y = 0x01;
+    let start = cpu.cycles;
+    step_logged(&mut cpu);
+    assert_eq!(cpu.cycles - start, 6);
+    assert_eq!(cpu.mem_read(0x02f1), 0x22);
+}
+
+#[test]
+fn test_jmp_indirect_page_wrap_bug() {
+    let mut cpu = CPU::new();
+    //JMP ($02FF)
+    cpu.load_program(vec![0x6c, 0xff, 0x02]);
+    cpu.reset();
+    cpu.mem_write(0x02ff, 0x00);
+    cpu.mem_write(0x0200, 0x03);
+    cpu.mem_write(0x0300, 0x04);
+    cpu.step();
+    assert_eq!(cpu.program_counter, 0x0300);
+}
+
+#[test]
+fn test_branch_backward_loop() {
+    let mut cpu = CPU::new();
+    //LDX #$05; loop: INY; DEX; BNE loop; BRK
+    cpu.load_program(vec![0xa2, 0x05, 0xc8, 0xca, 0xd0, 0xfc, 0x00]);
+    cpu.reset();
+    cpu.interpret();
+    assert_eq!(cpu.register_x, 0x00);
+    assert_eq!(cpu.register_y, 0x05);
+    assert_eq!(cpu.program_counter, 0x8006);
+}
+
+#[test]
+fn test_branch_offset_edges() {
+    let mut cpu = CPU::new();
+    //BPL +$7F
+    cpu.load_program(vec![0x10, 0x7f]);
+    cpu.reset();
+    cpu.step();
+    assert_eq!(cpu.program_counter, 0x8081);
+
+    //BPL -$80
+    cpu.load_program(vec![0x10, 0x80]);
+    cpu.reset();
+    cpu.step();
+    assert_eq!(cpu.program_counter, 0x7f82);
+
+    //BPL +0 lands on the next instruction either way
+    cpu.load_program(vec![0x10, 0x00]);
+    cpu.reset();
+    cpu.step();
+    assert_eq!(cpu.program_counter, 0x8002);
+}
+
+#[test]
+fn test_all_branches_follow_their_flag() {
+    let branches = [
+        (0x10, NEGETIVE, false),
+        (0x30, NEGETIVE, true),
+        (0x50, OVERFLOW, false),
+        (0x70, OVERFLOW, true),
+        (0x90, CARRY, false),
+        (0xb0, CARRY, true),
+        (0xd0, ZERO, false),
+        (0xf0, ZERO, true),
+    ];
+    for (opcode, flag, when_set) in branches {
+        for set in [false, true] {
+            let mut cpu = CPU::new();
+            cpu.load_program(vec![opcode, 0x10]);
+            cpu.reset();
+            cpu.set_flag(flag, set);
+            cpu.step();
+            let expected = if set == when_set { 0x8012 } else { 0x8002 };
+            assert_eq!(cpu.program_counter, expected, "opcode {:02X}", opcode);
+        }
+    }
+}
+
+#[test]
+fn test_branch_cycles_and_page_cross_read() {
+    let mut cpu = CPU::new();
+    cpu.mem_write(0x06f0, 0xd0);
+    cpu.mem_write(0x06f1, 0x10);
+    cpu.mem_write(0x06f2, 0xea);
+
+    // not taken
+    cpu.program_counter = 0x06f0;
+    cpu.set_flag(ZERO, true);
+    let start = cpu.cycles;
+    assert_eq!(step_logged(&mut cpu).len(), 2);
+    assert_eq!(cpu.cycles - start, 2);
+    assert_eq!(cpu.program_counter, 0x06f2);
+
+    // taken into $0702, first reading $0602
+    cpu.program_counter = 0x06f0;
+    cpu.set_flag(ZERO, false);
+    let start = cpu.cycles;
+    assert_eq!(
+        step_logged(&mut cpu)[2..],
+        [BusAccess::read(0x06f2, 0xea), BusAccess::read(0x0602, 0x00)]
+    );
+    assert_eq!(cpu.cycles - start, 4);
+    assert_eq!(cpu.program_counter, 0x0702);
+
+    // taken within the page
+    cpu.mem_write(0x06f1, 0x02);
+    cpu.program_counter = 0x06f0;
+    let start = cpu.cycles;
+    assert_eq!(step_logged(&mut cpu).len(), 3);
+    assert_eq!(cpu.cycles - start, 3);
+    assert_eq!(cpu.program_counter, 0x06f4);
+}
+
+#[test]
+fn test_brk_is_a_software_interrupt() {
+    let mut cpu = CPU::new();
+    cpu.mem_write_u16(0xFFFE, 0x9000);
+    //handler: INY; RTI
+    cpu.memory[0x9000] = 0xc8;
+    cpu.memory[0x9001] = 0x40;
+    //BRK, padding byte; INX
+    cpu.load_program(vec![0x00, 0xff, 0xe8, 0x00]);
+    cpu.reset();
+    cpu.status_register = CARRY;
+    let start = cpu.cycles;
+    assert!(cpu.step());
+    assert_eq!(cpu.cycles - start, 7);
+    assert_eq!(cpu.program_counter, 0x9000);
+    assert_eq!(cpu.status_register, CARRY | INTERRUPT);
+    assert_eq!(cpu.mem_read(0x01fd), 0x80);
+    assert_eq!(cpu.mem_read(0x01fc), 0x02);
+    assert_eq!(cpu.mem_read(0x01fb), CARRY | BREAK | BREAK2);
+
+    cpu.interpret();
+    assert_eq!(cpu.register_y, 1);
+    assert_eq!(cpu.register_x, 1);
+    assert_eq!(cpu.program_counter, 0x8003);
+    assert_eq!(cpu.status_register, CARRY | BREAK2);
+}
+
+#[test]
+fn test_load_image_wraps_around_memory() {
+    let mut cpu = CPU::new();
+    cpu.machine = Machine::Generic6502;
+    let mut image = vec![0u8; 0x10000];
+    image[0] = 0x11;
+    image[0xffff] = 0x22;
+    cpu.load_image(&image, 0x0200).unwrap();
+    assert_eq!(cpu.mem_read(0x0200), 0x11);
+    assert_eq!(cpu.mem_read(0x01ff), 0x22);
+    assert!(cpu.load_image(&[0; 0x10001], 0).is_err());
+}
+
+#[test]
+fn test_program_counter_wraps_at_the_top_of_memory() {
+    let mut cpu = CPU::new();
+    cpu.machine = Machine::Generic6502;
+    //LDA #$44 at $FFFE
+    cpu.load_image(&[0xa9, 0x44], 0xfffe).unwrap();
+    cpu.program_counter = 0xfffe;
+    cpu.step();
+    assert_eq!(cpu.register_a, 0x44);
+    assert_eq!(cpu.program_counter, 0x0000);
+
+    //LDA $1234 at $FFFF, the operand at $0000
+    cpu.load_image(&[0xad, 0x34, 0x12], 0xffff).unwrap();
+    cpu.mem_write(0x1234, 0x55);
+    cpu.program_counter = 0xffff;
+    cpu.step();
+    assert_eq!(cpu.register_a, 0x55);
+    assert_eq!(cpu.program_counter, 0x0002);
+
+    //BNE +2 at $FFFE lands past $0000
+    cpu.load_image(&[0xd0, 0x02], 0xfffe).unwrap();
+    cpu.program_counter = 0xfffe;
+    cpu.step();
+    assert_eq!(cpu.program_counter, 0x0002);
+
+    //JSR at $FFFD pushes $FFFF
+    cpu.load_image(&[0x20, 0x00, 0x03], 0xfffd).unwrap();
+    cpu.program_counter = 0xfffd;
+    cpu.step();
+    assert_eq!(cpu.program_counter, 0x0300);
+    assert_eq!(cpu.stack_pop_u16(), 0xffff);
+}
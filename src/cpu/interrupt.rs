@@ -0,0 +1,25 @@
+pub struct Interrupt {
+    pub vector_addr: u16,
+    // B flag bits pushed along with the status register
+    pub b_flag_mask: u8,
+    pub cpu_cycles: u8,
+}
+
+pub const NMI: Interrupt = Interrupt {
+    vector_addr: 0xFFFA,
+    b_flag_mask: 0b0010_0000,
+    cpu_cycles: 7,
+};
+
+pub const IRQ: Interrupt = Interrupt {
+    vector_addr: 0xFFFE,
+    b_flag_mask: 0b0010_0000,
+    cpu_cycles: 7,
+};
+
+// BRK goes through the IRQ vector, its pushed copy of P has B set
+pub const BRK: Interrupt = Interrupt {
+    vector_addr: 0xFFFE,
+    b_flag_mask: 0b0011_0000,
+    cpu_cycles: 7,
+};
@@ -1 +1,5 @@
+pub mod bus;
+pub mod interrupt;
 pub mod opcode_implementation;
+pub mod opcodes;
+pub mod trace;
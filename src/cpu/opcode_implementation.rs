@@ -1,10 +1,33 @@
-const CARRY: u8 = 0b0000_0001;
-const ZERO: u8 = 0b0000_0010;
-const INTERRUPT: u8 = 0b0000_0100;
-const DECIMAL: u8 = 0b0000_1000;
-const OVERFLOW: u8 = 0b0100_0000;
-const NEGETIVE: u8 = 0b1000_0000;
+use super::bus::BusAccess;
+use super::interrupt::{self, Interrupt};
+use super::opcodes::{OPCODES, OPCODE_CYCLES};
+use super::trace::{ppu_position, TraceLogger};
+use crate::apu::{Region, APU};
+use crate::cartridge::header::Timing;
+use crate::cartridge::Rom;
+use crate::input::InputPorts;
+use crate::render::frame::Frame;
 
+pub const CARRY: u8 = 0b0000_0001;
+pub const ZERO: u8 = 0b0000_0010;
+pub const INTERRUPT: u8 = 0b0000_0100;
+pub const DECIMAL: u8 = 0b0000_1000;
+pub const BREAK: u8 = 0b0001_0000;
+pub const BREAK2: u8 = 0b0010_0000;
+pub const OVERFLOW: u8 = 0b0100_0000;
+pub const NEGETIVE: u8 = 0b1000_0000;
+
+const STACK: u16 = 0x0100;
+const STACK_RESET: u8 = 0xFD;
+// ANE and LXA OR the accumulator with a constant that depends on the chip and
+// even its temperature; $EE is the value emulators and test suites settle on
+pub const DEFAULT_MAGIC: u8 = 0xEE;
+// a DMC sample fetch halts the CPU for up to four cycles, four is the usual case
+const DMC_STALL_CYCLES: usize = 4;
+// test programs end with one, see interpret
+pub const BRK_OPCODE: u8 = 0x00;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressingMode {
     Immediate,
     ZeroPage,
@@ -15,27 +38,73 @@ pub enum AddressingMode {
     AbsoluteY,
     IndirectX,
     IndirectY,
+    // only JMP, the pointer's high byte doesn't carry into the next page
+    Indirect,
+    Relative,
+    Accumulator,
     NoneAddressing,
 }
 
+// What the CPU is wired into. On the NES the APU and controllers sit at
+// $4000-$4017 and the 2A03 ignores the decimal flag. A generic 6502 sees
+// plain RAM everywhere and does BCD arithmetic with D set, which is what
+// standalone CPU test suites expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    Nes,
+    Generic6502,
+}
+
 pub struct CPU {
     pub register_a: u8,
     pub register_x: u8,
     pub register_y: u8,
     pub status_register: u8,
     pub program_counter: u16,
-    memory: [u8; 0xFFFF],
+    pub stack_pointer: u8,
+    pub cycles: usize,
+    // set by a JAM opcode: the CPU stops for good, with the program counter
+    // on the JAM, and only a reset brings it back
+    pub jammed: bool,
+    pub machine: Machine,
+    // see DEFAULT_MAGIC
+    pub magic: u8,
+    memory: [u8; 0x10000],
+    pub apu: APU,
+    pub input: InputPorts,
+    // video output, hosts take screenshots and feed light guns from it
+    pub frame: Frame,
+    // instruction log, see trace.rs
+    pub trace: Option<TraceLogger>,
+    // every bus read and write in order, while set
+    pub bus_log: Option<Vec<BusAccess>>,
+}
+
+impl Default for CPU {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CPU {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             register_a: 0,
             register_x: 0,
             register_y: 0,
             status_register: 0,
             program_counter: 0,
-            memory: [0 as u8; 0xFFFF],
+            stack_pointer: STACK_RESET,
+            cycles: 0,
+            jammed: false,
+            machine: Machine::Nes,
+            magic: DEFAULT_MAGIC,
+            memory: [0; 0x10000],
+            apu: APU::new(),
+            input: InputPorts::new(),
+            frame: Frame::new(),
+            trace: None,
+            bus_log: None,
         }
     }
 
@@ -44,6 +113,22 @@ impl CPU {
         self.register_x = 0;
         self.register_y = 0;
         self.status_register = 0;
+        self.stack_pointer = STACK_RESET;
+        self.jammed = false;
+        self.program_counter = self.mem_read_u16(0xFFFC);
+    }
+
+    // The reset button: registers and RAM are kept, the stack pointer moves
+    // down three bytes as if an interrupt happened without the writes, IRQs
+    // are masked and the sound channels are silenced.
+    pub fn soft_reset(&mut self) {
+        self.stack_pointer = self.stack_pointer.wrapping_sub(3);
+        self.status_register |= INTERRUPT;
+        self.jammed = false;
+        if self.machine == Machine::Nes {
+            self.apu.write_register(0x4015, 0);
+        }
+        self.tick(7);
         self.program_counter = self.mem_read_u16(0xFFFC);
     }
 
@@ -52,195 +137,674 @@ impl CPU {
         self.mem_write_u16(0xFFFC, 0x8000);
     }
 
+    // Copies a raw memory image to `addr`, for programs that aren't
+    // cartridges. Whatever runs past $FFFF wraps around to $0000, as the
+    // address bus does, but the image can't be bigger than memory.
+    pub fn load_image(&mut self, image: &[u8], addr: u16) -> Result<(), String> {
+        if image.len() > self.memory.len() {
+            return Err(format!(
+                "a {} byte image doesn't fit in 64 KiB of memory",
+                image.len()
+            ));
+        }
+        let (head, tail) = image.split_at(image.len().min(self.memory.len() - addr as usize));
+        let start = addr as usize;
+        self.memory[start..start + head.len()].copy_from_slice(head);
+        self.memory[..tail.len()].copy_from_slice(tail);
+        Ok(())
+    }
+
+    // Only NROM for now: PRG ROM sits at $8000, a 16 KiB image is mirrored
+    // into $C000. Also picks the region and input devices from the header.
+    pub fn load_rom(&mut self, rom: &Rom) -> Result<(), String> {
+        if rom.header.mapper != 0 {
+            return Err(format!("mapper {} is not supported", rom.header.mapper));
+        }
+        match rom.prg_rom.len() {
+            0x4000 => {
+                self.memory[0x8000..0xC000].copy_from_slice(&rom.prg_rom);
+                self.memory[0xC000..0x10000].copy_from_slice(&rom.prg_rom);
+            }
+            0x8000 => self.memory[0x8000..0x10000].copy_from_slice(&rom.prg_rom),
+            size => return Err(format!("{} bytes of PRG ROM is not an NROM size", size)),
+        }
+        if let Some(trainer) = &rom.trainer {
+            self.memory[0x7000..0x7200].copy_from_slice(trainer);
+        }
+        let region = match rom.header.timing {
+            Timing::Pal => Region::Pal,
+            _ => Region::Ntsc,
+        };
+        self.apu = APU::with_region(region);
+        self.input = InputPorts::for_header(&rom.header);
+        Ok(())
+    }
+
     pub fn run_program(&mut self, program: Vec<u8>) {
         self.load_program(program);
         self.reset();
         self.interpret();
     }
 
+    // Runs until the next instruction is a BRK, the way the small test
+    // programs end, or until the CPU jams. The BRK itself isn't executed.
     pub fn interpret(&mut self) {
-        loop {
-            let opcode: u8 = self.mem_read(self.program_counter);
-            println!("op: {:X}", opcode);
-            self.program_counter += 1;
-            match opcode {
-                //INX implied opcode
-                0xE8 => {
-                    self.register_x = self.register_x.wrapping_add(1);
-                    self.set_zero_flag(self.register_x);
-                    self.set_negetive_flag(self.register_x);
-                }
-                //AND immediate
-                0x29 => {
-                    let address = self.get_operand_address(&AddressingMode::Immediate);
-                    self.and(address);
-                }
-                //AND ZeroPage
-                0x25 => {
-                    let address = self.get_operand_address(&AddressingMode::ZeroPage);
-                    self.and(address);
-                }
-                //AND ZeroPageX
-                0x35 => {
-                    let address = self.get_operand_address(&AddressingMode::ZeroPageX);
-                    self.and(address);
-                }
-                //AND Absolute
-                0x2D => {
-                    let address = self.get_operand_address(&AddressingMode::Absolute);
-                    self.and(address);
-                }
-                //AND AbsoluteX
-                0x3D => {
-                    let address = self.get_operand_address(&AddressingMode::AbsoluteX);
-                    self.and(address);
-                }
-                //AND AbsoluteY
-                0x39 => {
-                    let address = self.get_operand_address(&AddressingMode::AbsoluteY);
-                    self.and(address);
-                }
-                //AND IndirectX
-                0x21 => {
-                    let address = self.get_operand_address(&AddressingMode::IndirectX);
-                    self.and(address);
-                }
-                //AND IndirectY
-                0x31 => {
-                    let address = self.get_operand_address(&AddressingMode::IndirectY);
-                    self.and(address);
-                }
-                //ASL accumulator
-                0x0A => {
-                    self.set_carry_flag(self.register_a);
-                    self.register_a <<= 1;
-                    self.set_zero_flag(self.register_a);
-                    self.set_negetive_flag(self.register_a);
-                }
-                //ASL ZeroPage
-                0x06 => {
-                    let address = self.get_operand_address(&AddressingMode::ZeroPage);
-                    self.asl(address);
-                }
-                //ASL ZeroPageX
-                0x16 => {
-                    let address = self.get_operand_address(&AddressingMode::ZeroPageX);
-                    self.asl(address);
-                }
-                //ASL AbsoluteX
-                0x0E => {
-                    let address = self.get_operand_address(&AddressingMode::Absolute);
-                    self.asl(address);
-                }
-                //ASL AbsoluteX
-                0x1E => {
-                    let address = self.get_operand_address(&AddressingMode::Absolute);
-                    self.asl(address);
-                }
-                //BCS relative
-                0xB0 => {
-                    self.branch_if_true(self.status_register & CARRY == CARRY);
-                }
-                //BEQ relative
-                0xF0 => {
-                    self.branch_if_true(self.status_register & ZERO == ZERO);
-                }
-                //BMI relative
-                0x30 => {
-                    self.branch_if_true(self.status_register & NEGETIVE == NEGETIVE);
-                }
-                //BNE relative
-                0xD0 => {
-                    self.branch_if_true(!(self.status_register & ZERO == ZERO));
-                }
-                //BPL relative
-                0x10 => {
-                    self.branch_if_true(!(self.status_register & NEGETIVE == NEGETIVE));
-                }
-                //BIT bit test ZeroPage
-                0x24 => {
-                    let address = self.get_operand_address(&AddressingMode::ZeroPage);
-                    self.bit(address);
-                }
-                //BIT bit test Absolute
-                0x2C => {
-                    let address = self.get_operand_address(&AddressingMode::Absolute);
-                    self.bit(address);
-                }
-                //Lda immediate opcode
-                0xA9 => {
-                    let address = self.get_operand_address(&AddressingMode::Immediate);
-                    self.lda(address);
-                }
-                //Lda ZeroPage opcode
-                0xA5 => {
-                    let address = self.get_operand_address(&AddressingMode::ZeroPage);
-                    self.lda(address);
-                }
-                //Lda ZeroPageX opcode
-                0xB5 => {
-                    let address = self.get_operand_address(&AddressingMode::ZeroPageX);
-                    self.lda(address);
-                }
-                //Lda Absolute opcode
-                0xAD => {
-                    let address = self.get_operand_address(&AddressingMode::Absolute);
-                    self.lda(address);
-                }
-                //Lda AbsoluteX opcode
-                0xBD => {
-                    let address = self.get_operand_address(&AddressingMode::AbsoluteX);
-                    self.lda(address);
-                }
-                //Lda AbsoluteY opcode
-                0xB9 => {
-                    let address = self.get_operand_address(&AddressingMode::AbsoluteY);
-                    self.lda(address);
-                }
-                //Lda IndirectX opcode
-                0xA1 => {
-                    let address = self.get_operand_address(&AddressingMode::IndirectX);
-                    self.lda(address);
-                }
-                //Lda IndirectY opcode
-                0xB1 => {
-                    let address = self.get_operand_address(&AddressingMode::IndirectY);
-                    self.lda(address);
-                }
-                //TAX implied opcode
-                0xAA => {
-                    self.register_x = self.register_a;
-                    self.set_zero_flag(self.register_a);
-                    self.set_negetive_flag(self.register_a);
-                }
-                //BRk opcode
-                0x00 => {
-                    return;
-                }
-                _ => {
-                    println!("Entered wild Branch with opcode {:X}", opcode);
-                    panic!("wild branch");
-                }
-            }
-        }
-    }
-
-    fn branch_if_true(&mut self, value: bool) {
-        if value {
-            let address = self.get_operand_address(&AddressingMode::Immediate);
-            let value = self.mem_read(address);
-            self.program_counter += (value - 1) as u16;
-        } else {
-            self.program_counter += 1;
+        while self.peek(self.program_counter) != BRK_OPCODE && self.step() {}
+    }
+
+    // Executes a single instruction. Returns false once the CPU is jammed.
+    pub fn step(&mut self) -> bool {
+        if self.jammed {
+            return false;
+        }
+        if self.machine == Machine::Nes && self.apu.irq() && self.status_register & INTERRUPT == 0 {
+            self.interrupt(interrupt::IRQ);
+        }
+        if let Some(mut trace) = self.trace.take() {
+            trace.log(self);
+            self.trace = Some(trace);
+        }
+        let opcode: u8 = self.fetch();
+        let mode = OPCODES[opcode as usize].mode;
+        // single byte instructions still read the byte after the opcode
+        if OPCODES[opcode as usize].len == 1 {
+            self.mem_read(self.program_counter);
+        }
+        match opcode {
+            //INX implied opcode
+            0xE8 => {
+                self.register_x = self.register_x.wrapping_add(1);
+                self.set_zero_flag(self.register_x);
+                self.set_negetive_flag(self.register_x);
+            }
+            //AND immediate
+            0x29 => {
+                let address = self.get_operand_address(&AddressingMode::Immediate);
+                self.and(address);
+            }
+            //AND ZeroPage
+            0x25 => {
+                let address = self.get_operand_address(&AddressingMode::ZeroPage);
+                self.and(address);
+            }
+            //AND ZeroPageX
+            0x35 => {
+                let address = self.get_operand_address(&AddressingMode::ZeroPageX);
+                self.and(address);
+            }
+            //AND Absolute
+            0x2D => {
+                let address = self.get_operand_address(&AddressingMode::Absolute);
+                self.and(address);
+            }
+            //AND AbsoluteX
+            0x3D => {
+                let address = self.get_operand_address(&AddressingMode::AbsoluteX);
+                self.and(address);
+            }
+            //AND AbsoluteY
+            0x39 => {
+                let address = self.get_operand_address(&AddressingMode::AbsoluteY);
+                self.and(address);
+            }
+            //AND IndirectX
+            0x21 => {
+                let address = self.get_operand_address(&AddressingMode::IndirectX);
+                self.and(address);
+            }
+            //AND IndirectY
+            0x31 => {
+                let address = self.get_operand_address(&AddressingMode::IndirectY);
+                self.and(address);
+            }
+            //ASL accumulator
+            0x0A => {
+                self.set_carry_flag(self.register_a);
+                self.register_a <<= 1;
+                self.set_zero_flag(self.register_a);
+                self.set_negetive_flag(self.register_a);
+            }
+            //ASL ZeroPage
+            0x06 => {
+                let address = self.get_write_address(&AddressingMode::ZeroPage);
+                self.read_modify_write(address, CPU::asl);
+            }
+            //ASL ZeroPageX
+            0x16 => {
+                let address = self.get_write_address(&AddressingMode::ZeroPageX);
+                self.read_modify_write(address, CPU::asl);
+            }
+            //ASL Absolute
+            0x0E => {
+                let address = self.get_write_address(&AddressingMode::Absolute);
+                self.read_modify_write(address, CPU::asl);
+            }
+            //ASL AbsoluteX
+            0x1E => {
+                let address = self.get_write_address(&AddressingMode::AbsoluteX);
+                self.read_modify_write(address, CPU::asl);
+            }
+            //BCS relative
+            0xB0 => {
+                self.branch_if_true(self.status_register & CARRY == CARRY);
+            }
+            //BEQ relative
+            0xF0 => {
+                self.branch_if_true(self.status_register & ZERO == ZERO);
+            }
+            //BMI relative
+            0x30 => {
+                self.branch_if_true(self.status_register & NEGETIVE == NEGETIVE);
+            }
+            //BNE relative
+            0xD0 => {
+                self.branch_if_true(self.status_register & ZERO != ZERO);
+            }
+            //BPL relative
+            0x10 => {
+                self.branch_if_true(self.status_register & NEGETIVE != NEGETIVE);
+            }
+            //BIT bit test ZeroPage
+            0x24 => {
+                let address = self.get_operand_address(&AddressingMode::ZeroPage);
+                self.bit(address);
+            }
+            //BIT bit test Absolute
+            0x2C => {
+                let address = self.get_operand_address(&AddressingMode::Absolute);
+                self.bit(address);
+            }
+            //Lda immediate opcode
+            0xA9 => {
+                let address = self.get_operand_address(&AddressingMode::Immediate);
+                self.lda(address);
+            }
+            //Lda ZeroPage opcode
+            0xA5 => {
+                let address = self.get_operand_address(&AddressingMode::ZeroPage);
+                self.lda(address);
+            }
+            //Lda ZeroPageX opcode
+            0xB5 => {
+                let address = self.get_operand_address(&AddressingMode::ZeroPageX);
+                self.lda(address);
+            }
+            //Lda Absolute opcode
+            0xAD => {
+                let address = self.get_operand_address(&AddressingMode::Absolute);
+                self.lda(address);
+            }
+            //Lda AbsoluteX opcode
+            0xBD => {
+                let address = self.get_operand_address(&AddressingMode::AbsoluteX);
+                self.lda(address);
+            }
+            //Lda AbsoluteY opcode
+            0xB9 => {
+                let address = self.get_operand_address(&AddressingMode::AbsoluteY);
+                self.lda(address);
+            }
+            //Lda IndirectX opcode
+            0xA1 => {
+                let address = self.get_operand_address(&AddressingMode::IndirectX);
+                self.lda(address);
+            }
+            //Lda IndirectY opcode
+            0xB1 => {
+                let address = self.get_operand_address(&AddressingMode::IndirectY);
+                self.lda(address);
+            }
+            //TAX implied opcode
+            0xAA => {
+                self.register_x = self.register_a;
+                self.set_zero_flag(self.register_a);
+                self.set_negetive_flag(self.register_a);
+            }
+            //RTI implied
+            0x40 => {
+                self.stack_dummy_read();
+                self.status_register = self.stack_pop();
+                self.status_register &= !BREAK;
+                self.status_register |= BREAK2;
+                self.program_counter = self.stack_pop_u16();
+            }
+            //BCC relative
+            0x90 => {
+                self.branch_if_true(self.status_register & CARRY != CARRY);
+            }
+            //BVC relative
+            0x50 => {
+                self.branch_if_true(self.status_register & OVERFLOW != OVERFLOW);
+            }
+            //BVS relative
+            0x70 => {
+                self.branch_if_true(self.status_register & OVERFLOW == OVERFLOW);
+            }
+            //ORA
+            0x09 | 0x05 | 0x15 | 0x0D | 0x1D | 0x19 | 0x01 | 0x11 => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.set_register_a(self.register_a | value);
+            }
+            //EOR
+            0x49 | 0x45 | 0x55 | 0x4D | 0x5D | 0x59 | 0x41 | 0x51 => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.set_register_a(self.register_a ^ value);
+            }
+            //ADC
+            0x69 | 0x65 | 0x75 | 0x6D | 0x7D | 0x79 | 0x61 | 0x71 => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.add_to_register_a(value);
+            }
+            //SBC
+            0xE9 | 0xE5 | 0xF5 | 0xED | 0xFD | 0xF9 | 0xE1 | 0xF1 => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.subtract_from_register_a(value);
+            }
+            //CMP
+            0xC9 | 0xC5 | 0xD5 | 0xCD | 0xDD | 0xD9 | 0xC1 | 0xD1 => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.compare(self.register_a, value);
+            }
+            //CPX
+            0xE0 | 0xE4 | 0xEC => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.compare(self.register_x, value);
+            }
+            //CPY
+            0xC0 | 0xC4 | 0xCC => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.compare(self.register_y, value);
+            }
+            //LDX
+            0xA2 | 0xA6 | 0xB6 | 0xAE | 0xBE => {
+                let address = self.get_operand_address(&mode);
+                self.register_x = self.mem_read(address);
+                self.set_zero_flag(self.register_x);
+                self.set_negetive_flag(self.register_x);
+            }
+            //LDY
+            0xA0 | 0xA4 | 0xB4 | 0xAC | 0xBC => {
+                let address = self.get_operand_address(&mode);
+                self.register_y = self.mem_read(address);
+                self.set_zero_flag(self.register_y);
+                self.set_negetive_flag(self.register_y);
+            }
+            //STA
+            0x85 | 0x95 | 0x8D | 0x9D | 0x99 | 0x81 | 0x91 => {
+                let address = self.get_write_address(&mode);
+                self.mem_write(address, self.register_a);
+            }
+            //STX
+            0x86 | 0x96 | 0x8E => {
+                let address = self.get_write_address(&mode);
+                self.mem_write(address, self.register_x);
+            }
+            //STY
+            0x84 | 0x94 | 0x8C => {
+                let address = self.get_write_address(&mode);
+                self.mem_write(address, self.register_y);
+            }
+            //INC
+            0xE6 | 0xF6 | 0xEE | 0xFE => {
+                let address = self.get_write_address(&mode);
+                self.read_modify_write(address, CPU::inc);
+            }
+            //DEC
+            0xC6 | 0xD6 | 0xCE | 0xDE => {
+                let address = self.get_write_address(&mode);
+                self.read_modify_write(address, CPU::dec);
+            }
+            //LSR accumulator
+            0x4A => self.register_a = self.lsr(self.register_a),
+            //LSR
+            0x46 | 0x56 | 0x4E | 0x5E => {
+                let address = self.get_write_address(&mode);
+                self.read_modify_write(address, CPU::lsr);
+            }
+            //ROL accumulator
+            0x2A => self.register_a = self.rol(self.register_a),
+            //ROL
+            0x26 | 0x36 | 0x2E | 0x3E => {
+                let address = self.get_write_address(&mode);
+                self.read_modify_write(address, CPU::rol);
+            }
+            //ROR accumulator
+            0x6A => self.register_a = self.ror(self.register_a),
+            //ROR
+            0x66 | 0x76 | 0x6E | 0x7E => {
+                let address = self.get_write_address(&mode);
+                self.read_modify_write(address, CPU::ror);
+            }
+            //JMP Absolute
+            0x4C => {
+                self.program_counter = self.mem_read_u16(self.program_counter);
+            }
+            //JMP Indirect
+            0x6C => {
+                let ptr = self.mem_read_u16(self.program_counter);
+                // the high byte comes from the start of the same page
+                let lo = self.mem_read(ptr);
+                let hi = self.mem_read((ptr & 0xFF00) | (ptr.wrapping_add(1) & 0x00FF));
+                self.program_counter = u16::from_le_bytes([lo, hi]);
+            }
+            //JSR, pushes the address of its own last byte
+            0x20 => {
+                // the high byte of the target is only fetched after the push
+                let lo = self.mem_read(self.program_counter);
+                self.stack_dummy_read();
+                self.stack_push_u16(self.program_counter.wrapping_add(1));
+                let hi = self.mem_read(self.program_counter.wrapping_add(1));
+                self.program_counter = u16::from_le_bytes([lo, hi]);
+            }
+            //RTS, reads the pushed address once more before stepping past it
+            0x60 => {
+                self.stack_dummy_read();
+                let address = self.stack_pop_u16();
+                self.mem_read(address);
+                self.program_counter = address.wrapping_add(1);
+            }
+            //PHA
+            0x48 => self.stack_push(self.register_a),
+            //PHP, the pushed copy has both B bits set
+            0x08 => self.stack_push(self.status_register | BREAK | BREAK2),
+            //PLA
+            0x68 => {
+                self.stack_dummy_read();
+                let value = self.stack_pop();
+                self.set_register_a(value);
+            }
+            //PLP
+            0x28 => {
+                self.stack_dummy_read();
+                self.status_register = self.stack_pop();
+                self.status_register &= !BREAK;
+                self.status_register |= BREAK2;
+            }
+            //CLC
+            0x18 => self.status_register &= !CARRY,
+            //SEC
+            0x38 => self.status_register |= CARRY,
+            //CLI
+            0x58 => self.status_register &= !INTERRUPT,
+            //SEI
+            0x78 => self.status_register |= INTERRUPT,
+            //CLV
+            0xB8 => self.status_register &= !OVERFLOW,
+            //CLD
+            0xD8 => self.status_register &= !DECIMAL,
+            //SED, the 2A03 has no decimal mode but the flag still exists
+            0xF8 => self.status_register |= DECIMAL,
+            //TAY
+            0xA8 => {
+                self.register_y = self.register_a;
+                self.set_zero_flag(self.register_y);
+                self.set_negetive_flag(self.register_y);
+            }
+            //TXA
+            0x8A => self.set_register_a(self.register_x),
+            //TYA
+            0x98 => self.set_register_a(self.register_y),
+            //TSX
+            0xBA => {
+                self.register_x = self.stack_pointer;
+                self.set_zero_flag(self.register_x);
+                self.set_negetive_flag(self.register_x);
+            }
+            //TXS, leaves the flags alone
+            0x9A => self.stack_pointer = self.register_x,
+            //INY
+            0xC8 => {
+                self.register_y = self.register_y.wrapping_add(1);
+                self.set_zero_flag(self.register_y);
+                self.set_negetive_flag(self.register_y);
+            }
+            //DEX
+            0xCA => {
+                self.register_x = self.register_x.wrapping_sub(1);
+                self.set_zero_flag(self.register_x);
+                self.set_negetive_flag(self.register_x);
+            }
+            //DEY
+            0x88 => {
+                self.register_y = self.register_y.wrapping_sub(1);
+                self.set_zero_flag(self.register_y);
+                self.set_negetive_flag(self.register_y);
+            }
+            //NOP
+            0xEA => {}
+            //NOP unofficial implied
+            0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xFA => {}
+            //NOP unofficial with an operand, which is read and ignored
+            0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 | 0x04 | 0x44 | 0x64 | 0x14 | 0x34 | 0x54 | 0x74
+            | 0xD4 | 0xF4 | 0x0C | 0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => {
+                let address = self.get_operand_address(&mode);
+                self.mem_read(address);
+            }
+            //SBC unofficial duplicate
+            0xEB => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.subtract_from_register_a(value);
+            }
+            //LAX, LDA and LDX at once
+            0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.register_x = value;
+                self.set_register_a(value);
+            }
+            //SAX, stores A AND X
+            0x87 | 0x97 | 0x8F | 0x83 => {
+                let address = self.get_write_address(&mode);
+                self.mem_write(address, self.register_a & self.register_x);
+            }
+            //SLO, ASL then ORA
+            0x07 | 0x17 | 0x0F | 0x1F | 0x1B | 0x03 | 0x13 => {
+                let address = self.get_write_address(&mode);
+                let value = self.read_modify_write(address, CPU::asl);
+                self.set_register_a(self.register_a | value);
+            }
+            //RLA, ROL then AND
+            0x27 | 0x37 | 0x2F | 0x3F | 0x3B | 0x23 | 0x33 => {
+                let address = self.get_write_address(&mode);
+                let value = self.read_modify_write(address, CPU::rol);
+                self.set_register_a(self.register_a & value);
+            }
+            //SRE, LSR then EOR
+            0x47 | 0x57 | 0x4F | 0x5F | 0x5B | 0x43 | 0x53 => {
+                let address = self.get_write_address(&mode);
+                let value = self.read_modify_write(address, CPU::lsr);
+                self.set_register_a(self.register_a ^ value);
+            }
+            //RRA, ROR then ADC with the carry ROR left
+            0x67 | 0x77 | 0x6F | 0x7F | 0x7B | 0x63 | 0x73 => {
+                let address = self.get_write_address(&mode);
+                let value = self.read_modify_write(address, CPU::ror);
+                self.add_to_register_a(value);
+            }
+            //DCP, DEC then CMP
+            0xC7 | 0xD7 | 0xCF | 0xDF | 0xDB | 0xC3 | 0xD3 => {
+                let address = self.get_write_address(&mode);
+                let value = self.read_modify_write(address, |_, value| value.wrapping_sub(1));
+                self.compare(self.register_a, value);
+            }
+            //ISB, INC then SBC
+            0xE7 | 0xF7 | 0xEF | 0xFF | 0xFB | 0xE3 | 0xF3 => {
+                let address = self.get_write_address(&mode);
+                let value = self.read_modify_write(address, |_, value| value.wrapping_add(1));
+                self.subtract_from_register_a(value);
+            }
+            //ANC, AND with bit 7 copied to carry
+            0x0B | 0x2B => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.set_register_a(self.register_a & value);
+                self.set_carry_flag(self.register_a);
+            }
+            //ALR, AND then LSR A
+            0x4B => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.register_a = self.lsr(self.register_a & value);
+            }
+            //ARR, AND then ROR A with its own carry and overflow
+            0x6B => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.arr(value);
+            }
+            //SBX, X = (A AND X) - value with CMP's flags
+            0xCB => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                let register = self.register_a & self.register_x;
+                self.compare(register, value);
+                self.register_x = register.wrapping_sub(value);
+            }
+            //LAS, A, X and S all get memory AND S
+            0xBB => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address) & self.stack_pointer;
+                self.stack_pointer = value;
+                self.register_x = value;
+                self.set_register_a(value);
+            }
+            //ANE, unstable
+            0x8B => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.set_register_a((self.register_a | self.magic) & self.register_x & value);
+            }
+            //LXA, unstable
+            0xAB => {
+                let address = self.get_operand_address(&mode);
+                let value = self.mem_read(address);
+                self.register_x = (self.register_a | self.magic) & value;
+                self.set_register_a(self.register_x);
+            }
+            //SHA IndirectY
+            0x93 => {
+                let ptr = self.fetch();
+                let base = self.mem_read_zero_page_u16(ptr);
+                self.store_high_byte_and(base, self.register_y, self.register_a & self.register_x);
+            }
+            //SHA AbsoluteY
+            0x9F => {
+                let base = self.fetch_u16();
+                self.store_high_byte_and(base, self.register_y, self.register_a & self.register_x);
+            }
+            //SHX AbsoluteY
+            0x9E => {
+                let base = self.fetch_u16();
+                self.store_high_byte_and(base, self.register_y, self.register_x);
+            }
+            //SHY AbsoluteX
+            0x9C => {
+                let base = self.fetch_u16();
+                self.store_high_byte_and(base, self.register_x, self.register_y);
+            }
+            //TAS AbsoluteY, S = A AND X, then stored like SHA
+            0x9B => {
+                let base = self.fetch_u16();
+                self.stack_pointer = self.register_a & self.register_x;
+                self.store_high_byte_and(base, self.register_y, self.stack_pointer);
+            }
+            //BRK, an IRQ the program asks for. The byte after it is skipped.
+            0x00 => {
+                self.program_counter = self.program_counter.wrapping_add(1);
+                self.enter_interrupt(&interrupt::BRK);
+            }
+            //JAM, the CPU locks up
+            0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => {
+                self.jammed = true;
+                self.program_counter = self.program_counter.wrapping_sub(1);
+                return false;
+            }
+        }
+        self.tick(OPCODE_CYCLES[opcode as usize]);
+        true
+    }
+
+    // Runs until the cycle counter reaches `cycle`. Once the CPU has jammed
+    // the APU keeps being clocked on its own. Returns whether the CPU is
+    // still running.
+    pub fn run_until(&mut self, cycle: usize) -> bool {
+        let mut running = true;
+        while self.cycles < cycle {
+            if running {
+                running = self.step();
+            } else {
+                self.tick(1);
+            }
+        }
+        running
+    }
+
+    // Advances the rest of the system by the given number of CPU cycles,
+    // servicing any DMC sample fetch the APU asks for along the way.
+    pub fn tick(&mut self, cycles: u8) {
+        let mut remaining = cycles as usize;
+        while remaining > 0 {
+            remaining -= 1;
+            self.cycles += 1;
+            self.apu.tick();
+            if let Some(addr) = self.apu.dmc.sample_request() {
+                let value = self.mem_read(addr);
+                self.apu.dmc.fill_sample_buffer(value);
+                remaining += DMC_STALL_CYCLES;
+            }
+        }
+    }
+
+    fn interrupt(&mut self, interrupt: Interrupt) {
+        // two reads of the instruction that gets postponed
+        self.mem_read(self.program_counter);
+        self.mem_read(self.program_counter);
+        self.enter_interrupt(&interrupt);
+        self.tick(interrupt.cpu_cycles);
+    }
+
+    // The part hardware interrupts share with BRK: push PC and P, mask IRQs
+    // and jump through the vector.
+    fn enter_interrupt(&mut self, interrupt: &Interrupt) {
+        self.stack_push_u16(self.program_counter);
+        let flags = (self.status_register & !(BREAK | BREAK2)) | interrupt.b_flag_mask;
+        self.stack_push(flags);
+        self.status_register |= INTERRUPT;
+        self.program_counter = self.mem_read_u16(interrupt.vector_addr);
+    }
+
+    // The offset is signed and counts from the next instruction. A taken
+    // branch costs a cycle, and another if the target is on a different page.
+    fn branch_if_true(&mut self, condition: bool) {
+        let offset = self.fetch() as i8;
+        if !condition {
+            return;
         }
+        self.mem_read(self.program_counter);
+        self.tick(1);
+        let target = self.program_counter.wrapping_add(offset as u16);
+        if (self.program_counter ^ target) & 0xFF00 != 0 {
+            // read before the high byte is fixed up
+            self.mem_read((self.program_counter & 0xFF00) | (target & 0x00FF));
+            self.tick(1);
+        }
+        self.program_counter = target;
     }
-    fn asl(&mut self, address: u16) {
-        let mut mem_value = self.mem_read(address);
-        self.set_carry_flag(mem_value);
-        mem_value <<= 1;
-        self.mem_write(address, mem_value);
-        self.set_zero_flag(mem_value);
-        self.set_negetive_flag(mem_value);
+
+    fn asl(&mut self, value: u8) -> u8 {
+        self.set_carry_flag(value);
+        let result = value << 1;
+        self.set_zero_flag(result);
+        self.set_negetive_flag(result);
+        result
     }
 
     fn and(&mut self, address: u16) {
@@ -265,70 +829,309 @@ impl CPU {
         self.set_overflow_flag(value);
     }
 
+    fn set_register_a(&mut self, value: u8) {
+        self.register_a = value;
+        self.set_zero_flag(value);
+        self.set_negetive_flag(value);
+    }
+
+    fn decimal_mode(&self) -> bool {
+        self.machine == Machine::Generic6502 && self.status_register & DECIMAL == DECIMAL
+    }
+
+    fn add_to_register_a(&mut self, value: u8) {
+        if self.decimal_mode() {
+            self.add_decimal_to_register_a(value);
+        } else {
+            self.add_binary_to_register_a(value);
+        }
+    }
+
+    // A + value + carry, setting carry and overflow
+    fn add_binary_to_register_a(&mut self, value: u8) {
+        let sum = self.register_a as u16 + value as u16 + (self.status_register & CARRY) as u16;
+        let result = sum as u8;
+        self.set_flag(CARRY, sum > 0xFF);
+        // overflow when both inputs have the same sign and the result doesn't
+        self.set_flag(
+            OVERFLOW,
+            (self.register_a ^ result) & (value ^ result) & NEGETIVE != 0,
+        );
+        self.set_register_a(result);
+    }
+
+    // A - value - borrow, where the borrow is a clear carry. Adding the ones'
+    // complement does exactly that in binary.
+    fn subtract_from_register_a(&mut self, value: u8) {
+        let a = self.register_a;
+        let carry = (self.status_register & CARRY) as i16;
+        self.add_binary_to_register_a(!value);
+        // the NMOS 6502 sets every flag as in binary, only A is adjusted
+        if self.decimal_mode() {
+            let mut low = (a & 0x0F) as i16 - (value & 0x0F) as i16 + carry - 1;
+            if low < 0 {
+                low = ((low - 0x06) & 0x0F) - 0x10;
+            }
+            let mut result = (a & 0xF0) as i16 - (value & 0xF0) as i16 + low;
+            if result < 0 {
+                result -= 0x60;
+            }
+            self.register_a = result as u8;
+        }
+    }
+
+    // BCD addition as the NMOS 6502 does it: Z comes from the binary sum,
+    // N and V from the sum before the high digit is adjusted.
+    fn add_decimal_to_register_a(&mut self, value: u8) {
+        let a = self.register_a;
+        let carry = self.status_register & CARRY;
+        let mut low = (a & 0x0F) + (value & 0x0F) + carry;
+        if low >= 0x0A {
+            low = ((low + 0x06) & 0x0F) + 0x10;
+        }
+        let high = (a & 0xF0) as i8 as i16 + (value & 0xF0) as i8 as i16 + low as i16;
+        let mut sum = (a & 0xF0) as u16 + (value & 0xF0) as u16 + low as u16;
+        if sum >= 0xA0 {
+            sum += 0x60;
+        }
+        self.set_zero_flag(a.wrapping_add(value).wrapping_add(carry));
+        self.set_negetive_flag(high as u8);
+        self.set_flag(OVERFLOW, !(-128..=127).contains(&high));
+        self.set_flag(CARRY, sum >= 0x100);
+        self.register_a = sum as u8;
+    }
+
+    fn compare(&mut self, register: u8, value: u8) {
+        self.set_flag(CARRY, register >= value);
+        let result = register.wrapping_sub(value);
+        self.set_zero_flag(result);
+        self.set_negetive_flag(result);
+    }
+
+    fn read_modify_write(&mut self, address: u16, operation: fn(&mut CPU, u8) -> u8) -> u8 {
+        let value = self.mem_read(address);
+        // the unmodified value is written back while the ALU works
+        self.mem_write(address, value);
+        let result = operation(self, value);
+        self.mem_write(address, result);
+        result
+    }
+
+    // ROR of A AND value, except that C is bit 6 of the result and V is bit 6
+    // XOR bit 5. In decimal mode the NMOS 6502 also adjusts each digit.
+    fn arr(&mut self, value: u8) {
+        let and = self.register_a & value;
+        let carry_in = self.status_register & CARRY;
+        let mut result = (and >> 1) | (carry_in << 7);
+        self.set_zero_flag(result);
+        self.set_negetive_flag(result);
+        if !self.decimal_mode() {
+            self.set_flag(CARRY, result & 0b0100_0000 != 0);
+            self.set_flag(OVERFLOW, (result ^ (result << 1)) & 0b0100_0000 != 0);
+            self.register_a = result;
+            return;
+        }
+        self.set_flag(OVERFLOW, (and ^ result) & 0b0100_0000 != 0);
+        let (high, low) = (and >> 4, and & 0x0F);
+        if low + (low & 1) > 5 {
+            result = (result & 0xF0) | (result.wrapping_add(6) & 0x0F);
+        }
+        let carry = high + (high & 1) > 5;
+        self.set_flag(CARRY, carry);
+        if carry {
+            result = result.wrapping_add(0x60);
+        }
+        self.register_a = result;
+    }
+
+    // SHA, SHX, SHY and TAS store the value ANDed with the high byte of the
+    // base address plus one. When indexing crosses a page the stored value
+    // also ends up as the high byte of the address.
+    fn store_high_byte_and(&mut self, base: u16, index: u8, value: u8) {
+        let [_, high] = base.to_le_bytes();
+        let value = value & high.wrapping_add(1);
+        let mut address = self.index_address(base, index, true);
+        if (base ^ address) & 0xFF00 != 0 {
+            address = (address & 0x00FF) | ((value as u16) << 8);
+        }
+        self.mem_write(address, value);
+    }
+
+    fn inc(&mut self, value: u8) -> u8 {
+        let result = value.wrapping_add(1);
+        self.set_zero_flag(result);
+        self.set_negetive_flag(result);
+        result
+    }
+
+    fn dec(&mut self, value: u8) -> u8 {
+        let result = value.wrapping_sub(1);
+        self.set_zero_flag(result);
+        self.set_negetive_flag(result);
+        result
+    }
+
+    fn lsr(&mut self, value: u8) -> u8 {
+        self.set_flag(CARRY, value & 1 == 1);
+        let result = value >> 1;
+        self.set_zero_flag(result);
+        self.set_negetive_flag(result);
+        result
+    }
+
+    fn rol(&mut self, value: u8) -> u8 {
+        let carry_in = self.status_register & CARRY;
+        self.set_carry_flag(value);
+        let result = (value << 1) | carry_in;
+        self.set_zero_flag(result);
+        self.set_negetive_flag(result);
+        result
+    }
+
+    fn ror(&mut self, value: u8) -> u8 {
+        let carry_in = self.status_register & CARRY;
+        self.set_flag(CARRY, value & 1 == 1);
+        let result = (value >> 1) | (carry_in << 7);
+        self.set_zero_flag(result);
+        self.set_negetive_flag(result);
+        result
+    }
+
+    // Address of the operand for an instruction that only reads it.
     fn get_operand_address(&mut self, mode: &AddressingMode) -> u16 {
+        self.operand_address(mode, false)
+    }
+
+    // Address of the operand for a store or read-modify-write. These always
+    // spend the cycle an indexed read only needs on a page cross.
+    fn get_write_address(&mut self, mode: &AddressingMode) -> u16 {
+        self.operand_address(mode, true)
+    }
+
+    fn operand_address(&mut self, mode: &AddressingMode, write: bool) -> u16 {
         match mode {
             AddressingMode::Immediate => {
-                self.program_counter += 1;
-                self.program_counter - 1
+                let address = self.program_counter;
+                self.program_counter = self.program_counter.wrapping_add(1);
+                address
             }
 
-            AddressingMode::ZeroPage => {
-                self.program_counter += 1;
-                self.mem_read(self.program_counter - 1) as u16
-            }
+            AddressingMode::ZeroPage => self.fetch() as u16,
 
             AddressingMode::ZeroPageX => {
-                self.program_counter += 1;
-                self.mem_read(self.program_counter - 1)
-                    .wrapping_add(self.register_x) as u16
+                let base = self.fetch();
+                // read while the index is being added
+                self.mem_read(base as u16);
+                base.wrapping_add(self.register_x) as u16
             }
 
             AddressingMode::ZeroPageY => {
-                self.program_counter += 1;
-                self.mem_read(self.program_counter - 1)
-                    .wrapping_add(self.register_y) as u16
+                let base = self.fetch();
+                self.mem_read(base as u16);
+                base.wrapping_add(self.register_y) as u16
             }
 
-            AddressingMode::Absolute => {
-                self.program_counter += 2;
-                self.mem_read_u16(self.program_counter - 2)
-            }
+            AddressingMode::Absolute => self.fetch_u16(),
 
             AddressingMode::AbsoluteX => {
-                self.program_counter += 2;
-                self.mem_read_u16(self.program_counter - 2)
-                    .wrapping_add(self.register_x as u16)
+                let base = self.fetch_u16();
+                self.index_address(base, self.register_x, write)
             }
 
             AddressingMode::AbsoluteY => {
-                self.program_counter += 2;
-                self.mem_read_u16(self.program_counter - 2)
-                    .wrapping_add(self.register_y as u16)
+                let base = self.fetch_u16();
+                self.index_address(base, self.register_y, write)
             }
 
             AddressingMode::IndirectX => {
-                self.program_counter += 1;
-                let ptr = self.mem_read(self.program_counter - 1);
-                self.mem_read_u16(ptr.wrapping_add(self.register_x) as u16)
+                let ptr = self.fetch();
+                self.mem_read(ptr as u16);
+                self.mem_read_zero_page_u16(ptr.wrapping_add(self.register_x))
             }
 
+            // Y is added to the address the pointer holds, not to the pointer
             AddressingMode::IndirectY => {
-                self.program_counter += 1;
-                let ptr = self.mem_read(self.program_counter - 1);
-                self.mem_read_u16(ptr.wrapping_add(self.register_y) as u16)
+                let ptr = self.fetch();
+                let base = self.mem_read_zero_page_u16(ptr);
+                self.index_address(base, self.register_y, write)
             }
 
-            AddressingMode::NoneAddressing => {
-                panic!("Address mode error: NoneAddressing provided");
+            AddressingMode::Indirect
+            | AddressingMode::Relative
+            | AddressingMode::Accumulator
+            | AddressingMode::NoneAddressing => {
+                panic!("Address mode error: {:?} provided", mode);
             }
         }
     }
 
+    // base + index. The 6502 adds the index to the low byte first and reads
+    // from there, fixing up the high byte a cycle later if it carried. For a
+    // read that didn't cross a page the first read was the real one.
+    fn index_address(&mut self, base: u16, index: u8, write: bool) -> u16 {
+        let address = base.wrapping_add(index as u16);
+        let crossed = (base ^ address) & 0xFF00 != 0;
+        if crossed || write {
+            self.mem_read((base & 0xFF00) | (address & 0x00FF));
+        }
+        if crossed && !write {
+            self.tick(1);
+        }
+        address
+    }
+
+    fn stack_push(&mut self, value: u8) {
+        self.mem_write(STACK + self.stack_pointer as u16, value);
+        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+    }
+
+    // Reads the next byte of the instruction stream. The program counter
+    // wraps from $FFFF to $0000.
+    fn fetch(&mut self) -> u8 {
+        let value = self.mem_read(self.program_counter);
+        self.program_counter = self.program_counter.wrapping_add(1);
+        value
+    }
+
+    fn fetch_u16(&mut self) -> u16 {
+        let lo = self.fetch();
+        let hi = self.fetch();
+        u16::from_le_bytes([lo, hi])
+    }
+
+    // Pulls spend a cycle reading the stack before the pointer is moved.
+    fn stack_dummy_read(&mut self) {
+        self.mem_read(STACK + self.stack_pointer as u16);
+    }
+
+    fn stack_pop(&mut self) -> u8 {
+        self.stack_pointer = self.stack_pointer.wrapping_add(1);
+        self.mem_read(STACK + self.stack_pointer as u16)
+    }
+
+    fn stack_push_u16(&mut self, value: u16) {
+        let [lo, hi] = value.to_le_bytes();
+        self.stack_push(hi);
+        self.stack_push(lo);
+    }
+
+    fn stack_pop_u16(&mut self) -> u16 {
+        let lo = self.stack_pop();
+        let hi = self.stack_pop();
+        u16::from_le_bytes([lo, hi])
+    }
+
     fn mem_read_u16(&mut self, addr: u16) -> u16 {
-        let addr = addr as usize;
-        let bytes: [u8; 2] = [self.memory[addr], self.memory[addr + 1]];
-        let out = u16::from_le_bytes(bytes);
-        out
+        let lo = self.mem_read(addr);
+        let hi = self.mem_read(addr.wrapping_add(1));
+        u16::from_le_bytes([lo, hi])
+    }
+
+    // pointers in zero page wrap from $FF to $00
+    fn mem_read_zero_page_u16(&mut self, ptr: u8) -> u16 {
+        let lo = self.mem_read(ptr as u16);
+        let hi = self.mem_read(ptr.wrapping_add(1) as u16);
+        u16::from_le_bytes([lo, hi])
     }
 
     fn mem_write_u16(&mut self, addr: u16, value: u16) {
@@ -338,12 +1141,51 @@ impl CPU {
         self.memory[addr + 1] = b;
     }
 
-    fn mem_read(&self, addr: u16) -> u8 {
-        return self.memory[addr as usize];
+    pub fn mem_read(&mut self, addr: u16) -> u8 {
+        let value = match (self.machine, addr) {
+            (Machine::Nes, 0x4015) => self.apu.read_status(),
+            (Machine::Nes, 0x4016 | 0x4017) => {
+                // light guns look at the picture where the beam is right now
+                let (scanline, dot) = ppu_position(self.apu.region(), self.cycles);
+                self.input.update_video(&self.frame, scanline, dot);
+                self.input.read(addr)
+            }
+            _ => self.memory[addr as usize],
+        };
+        if let Some(log) = &mut self.bus_log {
+            log.push(BusAccess::read(addr, value));
+        }
+        value
+    }
+
+    // Reads without side effects, for the trace log and debugging. Registers
+    // whose reads change state come back as 0.
+    pub fn peek(&self, addr: u16) -> u8 {
+        match (self.machine, addr) {
+            (Machine::Nes, 0x4015..=0x4017) => 0,
+            _ => self.memory[addr as usize],
+        }
+    }
+
+    pub fn mem_write(&mut self, addr: u16, value: u8) {
+        if let Some(log) = &mut self.bus_log {
+            log.push(BusAccess::write(addr, value));
+        }
+        match (self.machine, addr) {
+            (Machine::Nes, 0x4000..=0x4013 | 0x4015 | 0x4017) => {
+                self.apu.write_register(addr, value)
+            }
+            (Machine::Nes, 0x4016) => self.input.write(value),
+            _ => self.memory[addr as usize] = value,
+        }
     }
 
-    fn mem_write(&mut self, addr: u16, value: u8) {
-        self.memory[addr as usize] = value;
+    fn set_flag(&mut self, flag: u8, set: bool) {
+        if set {
+            self.status_register |= flag;
+        } else {
+            self.status_register &= !flag;
+        }
     }
 
     fn set_overflow_flag(&mut self, value: u8) {
@@ -375,10 +1217,13 @@ impl CPU {
         } else {
             self.status_register &= !NEGETIVE;
         }
-        return;
     }
 }
 
 #[cfg(test)]
 #[path = "./cpu_test.rs"]
 mod cpu_tests;
+
+#[cfg(test)]
+#[path = "./single_step_test.rs"]
+mod single_step_tests;
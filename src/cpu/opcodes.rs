@@ -0,0 +1,314 @@
+use super::opcode_implementation::AddressingMode;
+
+// Base cycle count of every opcode, without page-crossing or branch penalties.
+#[rustfmt::skip]
+pub const OPCODE_CYCLES: [u8; 256] = [
+//  0  1  2  3  4  5  6  7  8  9  A  B  C  D  E  F
+    7, 6, 2, 8, 3, 3, 5, 5, 3, 2, 2, 2, 4, 4, 6, 6, // 0
+    2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7, // 1
+    6, 6, 2, 8, 3, 3, 5, 5, 4, 2, 2, 2, 4, 4, 6, 6, // 2
+    2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7, // 3
+    6, 6, 2, 8, 3, 3, 5, 5, 3, 2, 2, 2, 3, 4, 6, 6, // 4
+    2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7, // 5
+    6, 6, 2, 8, 3, 3, 5, 5, 4, 2, 2, 2, 5, 4, 6, 6, // 6
+    2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7, // 7
+    2, 6, 2, 6, 3, 3, 3, 3, 2, 2, 2, 2, 4, 4, 4, 4, // 8
+    2, 6, 2, 6, 4, 4, 4, 4, 2, 5, 2, 5, 5, 5, 5, 5, // 9
+    2, 6, 2, 6, 3, 3, 3, 3, 2, 2, 2, 2, 4, 4, 4, 4, // A
+    2, 5, 2, 5, 4, 4, 4, 4, 2, 4, 2, 4, 4, 4, 4, 4, // B
+    2, 6, 2, 8, 3, 3, 5, 5, 2, 2, 2, 2, 4, 4, 6, 6, // C
+    2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7, // D
+    2, 6, 2, 8, 3, 3, 5, 5, 2, 2, 2, 2, 4, 4, 6, 6, // E
+    2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7, // F
+];
+
+// Decoding information for one opcode, used by the disassembler. The
+// unofficial ones are marked so a trace can flag them the way nestest does.
+pub struct OpCode {
+    pub code: u8,
+    pub mnemonic: &'static str,
+    pub len: u8,
+    pub mode: AddressingMode,
+    pub official: bool,
+}
+
+impl OpCode {
+    const fn new(code: u8, mnemonic: &'static str, len: u8, mode: AddressingMode) -> Self {
+        Self {
+            code,
+            mnemonic,
+            len,
+            mode,
+            official: true,
+        }
+    }
+
+    const fn unofficial(code: u8, mnemonic: &'static str, len: u8, mode: AddressingMode) -> Self {
+        Self {
+            official: false,
+            ..Self::new(code, mnemonic, len, mode)
+        }
+    }
+}
+
+// Every opcode in order, so OPCODES[n].code == n. The twelve JAM opcodes lock
+// up the CPU; they are listed as one byte long.
+#[rustfmt::skip]
+pub static OPCODES: [OpCode; 256] = [
+OpCode::new(0x00, "BRK", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x01, "ORA", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0x02, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x03, "SLO", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0x04, "NOP", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x05, "ORA", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x06, "ASL", 2, AddressingMode::ZeroPage),
+    OpCode::unofficial(0x07, "SLO", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x08, "PHP", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x09, "ORA", 2, AddressingMode::Immediate),
+    OpCode::new(0x0A, "ASL", 1, AddressingMode::Accumulator),
+    OpCode::unofficial(0x0B, "ANC", 2, AddressingMode::Immediate),
+    OpCode::unofficial(0x0C, "NOP", 3, AddressingMode::Absolute),
+    OpCode::new(0x0D, "ORA", 3, AddressingMode::Absolute),
+    OpCode::new(0x0E, "ASL", 3, AddressingMode::Absolute),
+    OpCode::unofficial(0x0F, "SLO", 3, AddressingMode::Absolute),
+    OpCode::new(0x10, "BPL", 2, AddressingMode::Relative),
+    OpCode::new(0x11, "ORA", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x12, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x13, "SLO", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x14, "NOP", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x15, "ORA", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x16, "ASL", 2, AddressingMode::ZeroPageX),
+    OpCode::unofficial(0x17, "SLO", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x18, "CLC", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x19, "ORA", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x1A, "NOP", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x1B, "SLO", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x1C, "NOP", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x1D, "ORA", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x1E, "ASL", 3, AddressingMode::AbsoluteX),
+    OpCode::unofficial(0x1F, "SLO", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x20, "JSR", 3, AddressingMode::Absolute),
+    OpCode::new(0x21, "AND", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0x22, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x23, "RLA", 2, AddressingMode::IndirectX),
+    OpCode::new(0x24, "BIT", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x25, "AND", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x26, "ROL", 2, AddressingMode::ZeroPage),
+    OpCode::unofficial(0x27, "RLA", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x28, "PLP", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x29, "AND", 2, AddressingMode::Immediate),
+    OpCode::new(0x2A, "ROL", 1, AddressingMode::Accumulator),
+    OpCode::unofficial(0x2B, "ANC", 2, AddressingMode::Immediate),
+    OpCode::new(0x2C, "BIT", 3, AddressingMode::Absolute),
+    OpCode::new(0x2D, "AND", 3, AddressingMode::Absolute),
+    OpCode::new(0x2E, "ROL", 3, AddressingMode::Absolute),
+    OpCode::unofficial(0x2F, "RLA", 3, AddressingMode::Absolute),
+    OpCode::new(0x30, "BMI", 2, AddressingMode::Relative),
+    OpCode::new(0x31, "AND", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x32, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x33, "RLA", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x34, "NOP", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x35, "AND", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x36, "ROL", 2, AddressingMode::ZeroPageX),
+    OpCode::unofficial(0x37, "RLA", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x38, "SEC", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x39, "AND", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x3A, "NOP", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x3B, "RLA", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x3C, "NOP", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x3D, "AND", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x3E, "ROL", 3, AddressingMode::AbsoluteX),
+    OpCode::unofficial(0x3F, "RLA", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x40, "RTI", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x41, "EOR", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0x42, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x43, "SRE", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0x44, "NOP", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x45, "EOR", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x46, "LSR", 2, AddressingMode::ZeroPage),
+    OpCode::unofficial(0x47, "SRE", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x48, "PHA", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x49, "EOR", 2, AddressingMode::Immediate),
+    OpCode::new(0x4A, "LSR", 1, AddressingMode::Accumulator),
+    OpCode::unofficial(0x4B, "ALR", 2, AddressingMode::Immediate),
+    OpCode::new(0x4C, "JMP", 3, AddressingMode::Absolute),
+    OpCode::new(0x4D, "EOR", 3, AddressingMode::Absolute),
+    OpCode::new(0x4E, "LSR", 3, AddressingMode::Absolute),
+    OpCode::unofficial(0x4F, "SRE", 3, AddressingMode::Absolute),
+    OpCode::new(0x50, "BVC", 2, AddressingMode::Relative),
+    OpCode::new(0x51, "EOR", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x52, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x53, "SRE", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x54, "NOP", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x55, "EOR", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x56, "LSR", 2, AddressingMode::ZeroPageX),
+    OpCode::unofficial(0x57, "SRE", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x58, "CLI", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x59, "EOR", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x5A, "NOP", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x5B, "SRE", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x5C, "NOP", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x5D, "EOR", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x5E, "LSR", 3, AddressingMode::AbsoluteX),
+    OpCode::unofficial(0x5F, "SRE", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x60, "RTS", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x61, "ADC", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0x62, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x63, "RRA", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0x64, "NOP", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x65, "ADC", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x66, "ROR", 2, AddressingMode::ZeroPage),
+    OpCode::unofficial(0x67, "RRA", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x68, "PLA", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x69, "ADC", 2, AddressingMode::Immediate),
+    OpCode::new(0x6A, "ROR", 1, AddressingMode::Accumulator),
+    OpCode::unofficial(0x6B, "ARR", 2, AddressingMode::Immediate),
+    OpCode::new(0x6C, "JMP", 3, AddressingMode::Indirect),
+    OpCode::new(0x6D, "ADC", 3, AddressingMode::Absolute),
+    OpCode::new(0x6E, "ROR", 3, AddressingMode::Absolute),
+    OpCode::unofficial(0x6F, "RRA", 3, AddressingMode::Absolute),
+    OpCode::new(0x70, "BVS", 2, AddressingMode::Relative),
+    OpCode::new(0x71, "ADC", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x72, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x73, "RRA", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x74, "NOP", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x75, "ADC", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x76, "ROR", 2, AddressingMode::ZeroPageX),
+    OpCode::unofficial(0x77, "RRA", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x78, "SEI", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x79, "ADC", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x7A, "NOP", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x7B, "RRA", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x7C, "NOP", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x7D, "ADC", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x7E, "ROR", 3, AddressingMode::AbsoluteX),
+    OpCode::unofficial(0x7F, "RRA", 3, AddressingMode::AbsoluteX),
+    OpCode::unofficial(0x80, "NOP", 2, AddressingMode::Immediate),
+    OpCode::new(0x81, "STA", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0x82, "NOP", 2, AddressingMode::Immediate),
+    OpCode::unofficial(0x83, "SAX", 2, AddressingMode::IndirectX),
+    OpCode::new(0x84, "STY", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x85, "STA", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x86, "STX", 2, AddressingMode::ZeroPage),
+    OpCode::unofficial(0x87, "SAX", 2, AddressingMode::ZeroPage),
+    OpCode::new(0x88, "DEY", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x89, "NOP", 2, AddressingMode::Immediate),
+    OpCode::new(0x8A, "TXA", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x8B, "ANE", 2, AddressingMode::Immediate),
+    OpCode::new(0x8C, "STY", 3, AddressingMode::Absolute),
+    OpCode::new(0x8D, "STA", 3, AddressingMode::Absolute),
+    OpCode::new(0x8E, "STX", 3, AddressingMode::Absolute),
+    OpCode::unofficial(0x8F, "SAX", 3, AddressingMode::Absolute),
+    OpCode::new(0x90, "BCC", 2, AddressingMode::Relative),
+    OpCode::new(0x91, "STA", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0x92, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x93, "SHA", 2, AddressingMode::IndirectY),
+    OpCode::new(0x94, "STY", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x95, "STA", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0x96, "STX", 2, AddressingMode::ZeroPageY),
+    OpCode::unofficial(0x97, "SAX", 2, AddressingMode::ZeroPageY),
+    OpCode::new(0x98, "TYA", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0x99, "STA", 3, AddressingMode::AbsoluteY),
+    OpCode::new(0x9A, "TXS", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0x9B, "TAS", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x9C, "SHY", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0x9D, "STA", 3, AddressingMode::AbsoluteX),
+    OpCode::unofficial(0x9E, "SHX", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0x9F, "SHA", 3, AddressingMode::AbsoluteY),
+    OpCode::new(0xA0, "LDY", 2, AddressingMode::Immediate),
+    OpCode::new(0xA1, "LDA", 2, AddressingMode::IndirectX),
+    OpCode::new(0xA2, "LDX", 2, AddressingMode::Immediate),
+    OpCode::unofficial(0xA3, "LAX", 2, AddressingMode::IndirectX),
+    OpCode::new(0xA4, "LDY", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xA5, "LDA", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xA6, "LDX", 2, AddressingMode::ZeroPage),
+    OpCode::unofficial(0xA7, "LAX", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xA8, "TAY", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0xA9, "LDA", 2, AddressingMode::Immediate),
+    OpCode::new(0xAA, "TAX", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xAB, "LXA", 2, AddressingMode::Immediate),
+    OpCode::new(0xAC, "LDY", 3, AddressingMode::Absolute),
+    OpCode::new(0xAD, "LDA", 3, AddressingMode::Absolute),
+    OpCode::new(0xAE, "LDX", 3, AddressingMode::Absolute),
+    OpCode::unofficial(0xAF, "LAX", 3, AddressingMode::Absolute),
+    OpCode::new(0xB0, "BCS", 2, AddressingMode::Relative),
+    OpCode::new(0xB1, "LDA", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0xB2, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xB3, "LAX", 2, AddressingMode::IndirectY),
+    OpCode::new(0xB4, "LDY", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0xB5, "LDA", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0xB6, "LDX", 2, AddressingMode::ZeroPageY),
+    OpCode::unofficial(0xB7, "LAX", 2, AddressingMode::ZeroPageY),
+    OpCode::new(0xB8, "CLV", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0xB9, "LDA", 3, AddressingMode::AbsoluteY),
+    OpCode::new(0xBA, "TSX", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xBB, "LAS", 3, AddressingMode::AbsoluteY),
+    OpCode::new(0xBC, "LDY", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0xBD, "LDA", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0xBE, "LDX", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0xBF, "LAX", 3, AddressingMode::AbsoluteY),
+    OpCode::new(0xC0, "CPY", 2, AddressingMode::Immediate),
+    OpCode::new(0xC1, "CMP", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0xC2, "NOP", 2, AddressingMode::Immediate),
+    OpCode::unofficial(0xC3, "DCP", 2, AddressingMode::IndirectX),
+    OpCode::new(0xC4, "CPY", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xC5, "CMP", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xC6, "DEC", 2, AddressingMode::ZeroPage),
+    OpCode::unofficial(0xC7, "DCP", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xC8, "INY", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0xC9, "CMP", 2, AddressingMode::Immediate),
+    OpCode::new(0xCA, "DEX", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xCB, "SBX", 2, AddressingMode::Immediate),
+    OpCode::new(0xCC, "CPY", 3, AddressingMode::Absolute),
+    OpCode::new(0xCD, "CMP", 3, AddressingMode::Absolute),
+    OpCode::new(0xCE, "DEC", 3, AddressingMode::Absolute),
+    OpCode::unofficial(0xCF, "DCP", 3, AddressingMode::Absolute),
+    OpCode::new(0xD0, "BNE", 2, AddressingMode::Relative),
+    OpCode::new(0xD1, "CMP", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0xD2, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xD3, "DCP", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0xD4, "NOP", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0xD5, "CMP", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0xD6, "DEC", 2, AddressingMode::ZeroPageX),
+    OpCode::unofficial(0xD7, "DCP", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0xD8, "CLD", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0xD9, "CMP", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0xDA, "NOP", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xDB, "DCP", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0xDC, "NOP", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0xDD, "CMP", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0xDE, "DEC", 3, AddressingMode::AbsoluteX),
+    OpCode::unofficial(0xDF, "DCP", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0xE0, "CPX", 2, AddressingMode::Immediate),
+    OpCode::new(0xE1, "SBC", 2, AddressingMode::IndirectX),
+    OpCode::unofficial(0xE2, "NOP", 2, AddressingMode::Immediate),
+    OpCode::unofficial(0xE3, "ISB", 2, AddressingMode::IndirectX),
+    OpCode::new(0xE4, "CPX", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xE5, "SBC", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xE6, "INC", 2, AddressingMode::ZeroPage),
+    OpCode::unofficial(0xE7, "ISB", 2, AddressingMode::ZeroPage),
+    OpCode::new(0xE8, "INX", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0xE9, "SBC", 2, AddressingMode::Immediate),
+    OpCode::new(0xEA, "NOP", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xEB, "SBC", 2, AddressingMode::Immediate),
+    OpCode::new(0xEC, "CPX", 3, AddressingMode::Absolute),
+    OpCode::new(0xED, "SBC", 3, AddressingMode::Absolute),
+    OpCode::new(0xEE, "INC", 3, AddressingMode::Absolute),
+    OpCode::unofficial(0xEF, "ISB", 3, AddressingMode::Absolute),
+    OpCode::new(0xF0, "BEQ", 2, AddressingMode::Relative),
+    OpCode::new(0xF1, "SBC", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0xF2, "JAM", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xF3, "ISB", 2, AddressingMode::IndirectY),
+    OpCode::unofficial(0xF4, "NOP", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0xF5, "SBC", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0xF6, "INC", 2, AddressingMode::ZeroPageX),
+    OpCode::unofficial(0xF7, "ISB", 2, AddressingMode::ZeroPageX),
+    OpCode::new(0xF8, "SED", 1, AddressingMode::NoneAddressing),
+    OpCode::new(0xF9, "SBC", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0xFA, "NOP", 1, AddressingMode::NoneAddressing),
+    OpCode::unofficial(0xFB, "ISB", 3, AddressingMode::AbsoluteY),
+    OpCode::unofficial(0xFC, "NOP", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0xFD, "SBC", 3, AddressingMode::AbsoluteX),
+    OpCode::new(0xFE, "INC", 3, AddressingMode::AbsoluteX),
+    OpCode::unofficial(0xFF, "ISB", 3, AddressingMode::AbsoluteX),
+];
@@ -0,0 +1,184 @@
+// Runner for Tom Harte's ProcessorTests (SingleStepTests), the nes6502 set
+// that has decimal mode disabled. Each opcode has a file like a9.json with
+// thousands of cases, one instruction each: initial registers and RAM, the
+// expected result and every bus cycle in between. The files go in
+// processor-tests/ under the test ROM directory.
+use std::panic::{self, AssertUnwindSafe};
+
+use serde::Deserialize;
+
+use super::*;
+use crate::cpu::bus::BusAccess;
+use crate::headless::read_test_file;
+
+#[derive(Deserialize)]
+struct State {
+    pc: u16,
+    s: u8,
+    a: u8,
+    x: u8,
+    y: u8,
+    p: u8,
+    ram: Vec<(u16, u8)>,
+}
+
+#[derive(Deserialize)]
+struct Case {
+    name: String,
+    initial: State,
+    #[serde(rename = "final")]
+    expected: State,
+    cycles: Vec<(u16, u8, String)>,
+}
+
+impl Case {
+    // the tests assume RAM everywhere, here $4000-$4017 are registers
+    fn touches_io(&self) -> bool {
+        let io = |addr: &u16| (0x4000..=0x4017).contains(addr);
+        self.initial.ram.iter().any(|(addr, _)| io(addr))
+            || self.expected.ram.iter().any(|(addr, _)| io(addr))
+            || self.cycles.iter().any(|(addr, _, _)| io(addr))
+    }
+
+    fn bus_activity(&self) -> Vec<BusAccess> {
+        self.cycles
+            .iter()
+            .map(|(addr, value, operation)| match operation.as_str() {
+                "read" => BusAccess::read(*addr, *value),
+                _ => BusAccess::write(*addr, *value),
+            })
+            .collect()
+    }
+}
+
+fn run_case(case: &Case) -> Result<(), String> {
+    let mut cpu = CPU::new();
+    let initial = &case.initial;
+    cpu.program_counter = initial.pc;
+    cpu.stack_pointer = initial.s;
+    cpu.register_a = initial.a;
+    cpu.register_x = initial.x;
+    cpu.register_y = initial.y;
+    cpu.status_register = initial.p;
+    for &(addr, value) in &initial.ram {
+        cpu.mem_write(addr, value);
+    }
+    cpu.bus_log = Some(Vec::new());
+
+    if panic::catch_unwind(AssertUnwindSafe(|| cpu.step())).is_err() {
+        return Err("crashed".to_string());
+    }
+
+    let expected = &case.expected;
+    let mut errors = Vec::new();
+    let registers = [
+        ("PC", expected.pc, cpu.program_counter),
+        ("S", expected.s as u16, cpu.stack_pointer as u16),
+        ("A", expected.a as u16, cpu.register_a as u16),
+        ("X", expected.x as u16, cpu.register_x as u16),
+        ("Y", expected.y as u16, cpu.register_y as u16),
+        ("P", expected.p as u16, cpu.status_register as u16),
+    ];
+    for (name, expected, actual) in registers {
+        if expected != actual {
+            errors.push(format!(
+                "{} is {:02X}, expected {:02X}",
+                name, actual, expected
+            ));
+        }
+    }
+    for &(addr, value) in &expected.ram {
+        if cpu.peek(addr) != value {
+            errors.push(format!(
+                "${:04X} is {:02X}, expected {:02X}",
+                addr,
+                cpu.peek(addr),
+                value
+            ));
+        }
+    }
+    let bus = cpu.bus_log.take().unwrap_or_default();
+    if bus != case.bus_activity() {
+        errors.push(format!(
+            "bus activity is {:?}, expected {:?}",
+            bus,
+            case.bus_activity()
+        ));
+    }
+    if cpu.cycles != case.cycles.len() {
+        errors.push(format!(
+            "took {} cycles, expected {}",
+            cpu.cycles,
+            case.cycles.len()
+        ));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn parse(json: &str) -> Case {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_passing_case() {
+    //LDA #$42
+    let case = parse(
+        r#"{"name": "a9 42 00",
+        "initial": {"pc": 512, "s": 253, "a": 0, "x": 0, "y": 0, "p": 36, "ram": [[512, 169], [513, 66]]},
+        "final": {"pc": 514, "s": 253, "a": 66, "x": 0, "y": 0, "p": 36, "ram": [[512, 169], [513, 66]]},
+        "cycles": [[512, 169, "read"], [513, 66, "read"]]}"#,
+    );
+    assert_eq!(run_case(&case), Ok(()));
+    //STA $10
+    let case = parse(
+        r#"{"name": "85 10 00",
+        "initial": {"pc": 512, "s": 253, "a": 7, "x": 0, "y": 0, "p": 36, "ram": [[512, 133], [513, 16], [16, 0]]},
+        "final": {"pc": 514, "s": 253, "a": 7, "x": 0, "y": 0, "p": 36, "ram": [[512, 133], [513, 16], [16, 7]]},
+        "cycles": [[512, 133, "read"], [513, 16, "read"], [16, 7, "write"]]}"#,
+    );
+    assert_eq!(run_case(&case), Ok(()));
+}
+
+#[test]
+fn test_failing_case_reports_differences() {
+    let case = parse(
+        r#"{"name": "a9 42 00",
+        "initial": {"pc": 512, "s": 253, "a": 0, "x": 0, "y": 0, "p": 36, "ram": [[512, 169], [513, 66]]},
+        "final": {"pc": 514, "s": 253, "a": 67, "x": 0, "y": 0, "p": 36, "ram": [[512, 169], [513, 67]]},
+        "cycles": [[512, 169, "read"], [513, 66, "read"], [514, 0, "read"]]}"#,
+    );
+    let error = run_case(&case).unwrap_err();
+    assert!(error.contains("A is 42, expected 43"));
+    assert!(error.contains("$0201 is 42, expected 43"));
+    assert!(error.contains("bus activity is"));
+    assert!(error.contains("took 2 cycles, expected 3"));
+    assert!(!case.touches_io());
+}
+
+#[test]
+#[ignore = "needs processor-tests/00.json to ff.json, see test_roms_dir"]
+fn test_processor_tests() {
+    let mut failures = Vec::new();
+    for opcode in 0..=0xFF {
+        let json = read_test_file(&format!("processor-tests/{:02x}.json", opcode));
+        let cases: Vec<Case> = serde_json::from_slice(&json).unwrap();
+        // the first failing case is enough to go on
+        if let Some((case, error)) = cases
+            .iter()
+            .filter(|case| !case.touches_io())
+            .find_map(|case| run_case(case).err().map(|error| (case, error)))
+        {
+            failures.push(format!("{:02X} [{}]: {}", opcode, case.name, error));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} opcodes fail:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
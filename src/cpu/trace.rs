@@ -0,0 +1,187 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::opcode_implementation::{AddressingMode, CPU};
+use super::opcodes::OPCODES;
+use crate::apu::Region;
+
+// Writes one line per executed instruction in the Nintendulator / nestest.log
+// format, e.g.
+// C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+// The state shown is the one before the instruction runs.
+pub struct TraceLogger {
+    output: Box<dyn Write>,
+    enabled: bool,
+    // the first write error, logging stops once there is one
+    error: Option<io::Error>,
+}
+
+impl TraceLogger {
+    pub fn new(output: Box<dyn Write>) -> Self {
+        Self {
+            output,
+            enabled: true,
+            error: None,
+        }
+    }
+
+    pub fn to_file(path: &Path) -> io::Result<Self> {
+        Ok(Self::new(Box::new(BufWriter::new(File::create(path)?))))
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // Pauses or resumes logging without closing the output.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn log(&mut self, cpu: &CPU) {
+        if !self.enabled || self.error.is_some() {
+            return;
+        }
+        if let Err(err) = writeln!(self.output, "{}", trace_line(cpu)) {
+            self.error = Some(err);
+        }
+    }
+
+    // Flushes the output and reports any error hit while logging.
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(err) => Err(err),
+            None => self.output.flush(),
+        }
+    }
+}
+
+// The trace line for the instruction at the program counter.
+pub fn trace_line(cpu: &CPU) -> String {
+    let pc = cpu.program_counter;
+    let opcode = &OPCODES[cpu.peek(pc) as usize];
+    let bytes: Vec<String> = (0..opcode.len as u16)
+        .map(|i| format!("{:02X}", cpu.peek(pc.wrapping_add(i))))
+        .collect();
+    let (scanline, dot) = ppu_position(cpu.apu.region(), cpu.cycles);
+    format!(
+        "{:04X}  {:<8} {}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} PPU:{:>3},{:>3} CYC:{}",
+        pc,
+        bytes.join(" "),
+        if opcode.official { ' ' } else { '*' },
+        disassemble(cpu, pc),
+        cpu.register_a,
+        cpu.register_x,
+        cpu.register_y,
+        cpu.status_register,
+        cpu.stack_pointer,
+        scanline,
+        dot,
+        cpu.cycles
+    )
+}
+
+// The instruction at `pc` with its operand resolved against the current
+// registers and memory, the way Nintendulator prints it.
+pub fn disassemble(cpu: &CPU, pc: u16) -> String {
+    let opcode = &OPCODES[cpu.peek(pc) as usize];
+    let lo = cpu.peek(pc.wrapping_add(1));
+    let hi = cpu.peek(pc.wrapping_add(2));
+    let word = u16::from_le_bytes([lo, hi]);
+    let x = cpu.register_x;
+    let y = cpu.register_y;
+    let operand = match opcode.mode {
+        AddressingMode::NoneAddressing => String::new(),
+        AddressingMode::Accumulator => "A".to_string(),
+        AddressingMode::Immediate => format!("#${:02X}", lo),
+        AddressingMode::Relative => {
+            let target = pc.wrapping_add(2).wrapping_add(lo as i8 as u16);
+            format!("${:04X}", target)
+        }
+        AddressingMode::ZeroPage => format!("${:02X} = {:02X}", lo, cpu.peek(lo as u16)),
+        AddressingMode::ZeroPageX => {
+            let addr = lo.wrapping_add(x);
+            format!(
+                "${:02X},X @ {:02X} = {:02X}",
+                lo,
+                addr,
+                cpu.peek(addr as u16)
+            )
+        }
+        AddressingMode::ZeroPageY => {
+            let addr = lo.wrapping_add(y);
+            format!(
+                "${:02X},Y @ {:02X} = {:02X}",
+                lo,
+                addr,
+                cpu.peek(addr as u16)
+            )
+        }
+        // jumps show only the target
+        AddressingMode::Absolute if matches!(opcode.mnemonic, "JMP" | "JSR") => {
+            format!("${:04X}", word)
+        }
+        AddressingMode::Absolute => format!("${:04X} = {:02X}", word, cpu.peek(word)),
+        AddressingMode::AbsoluteX => {
+            let addr = word.wrapping_add(x as u16);
+            format!("${:04X},X @ {:04X} = {:02X}", word, addr, cpu.peek(addr))
+        }
+        AddressingMode::AbsoluteY => {
+            let addr = word.wrapping_add(y as u16);
+            format!("${:04X},Y @ {:04X} = {:02X}", word, addr, cpu.peek(addr))
+        }
+        AddressingMode::Indirect => {
+            // the high byte comes from the start of the same page
+            let hi_addr = (word & 0xFF00) | (word.wrapping_add(1) & 0x00FF);
+            let target = u16::from_le_bytes([cpu.peek(word), cpu.peek(hi_addr)]);
+            format!("(${:04X}) = {:04X}", word, target)
+        }
+        AddressingMode::IndirectX => {
+            let ptr = lo.wrapping_add(x);
+            let addr = peek_zero_page_u16(cpu, ptr);
+            format!(
+                "(${:02X},X) @ {:02X} = {:04X} = {:02X}",
+                lo,
+                ptr,
+                addr,
+                cpu.peek(addr)
+            )
+        }
+        AddressingMode::IndirectY => {
+            let base = peek_zero_page_u16(cpu, lo);
+            let addr = base.wrapping_add(y as u16);
+            format!(
+                "(${:02X}),Y = {:04X} @ {:04X} = {:02X}",
+                lo,
+                base,
+                addr,
+                cpu.peek(addr)
+            )
+        }
+    };
+    format!("{} {}", opcode.mnemonic, operand)
+        .trim_end()
+        .to_string()
+}
+
+// pointers in zero page wrap from $FF to $00
+fn peek_zero_page_u16(cpu: &CPU, ptr: u8) -> u16 {
+    u16::from_le_bytes([cpu.peek(ptr as u16), cpu.peek(ptr.wrapping_add(1) as u16)])
+}
+
+// Scanline and dot the PPU is at after `cycles` CPU cycles, counting from
+// the start of the first frame. Assumes rendering is off, so no frame is
+// ever a dot short.
+pub fn ppu_position(region: Region, cycles: usize) -> (usize, usize) {
+    let (dots, scanlines) = match region {
+        Region::Ntsc => (cycles * 3, 262),
+        // 3.2 dots per CPU cycle
+        Region::Pal => (cycles * 16 / 5, 312),
+    };
+    ((dots / 341) % scanlines, dots % 341)
+}
+
+#[cfg(test)]
+#[path = "./trace_test.rs"]
+mod trace_tests;
@@ -0,0 +1,125 @@
+use super::*;
+use crate::headless::TempDir;
+
+fn cpu_at(pc: u16, program: &[u8]) -> CPU {
+    let mut cpu = CPU::new();
+    for (i, &byte) in program.iter().enumerate() {
+        cpu.mem_write(pc + i as u16, byte);
+    }
+    cpu.program_counter = pc;
+    cpu
+}
+
+#[test]
+fn test_nestest_first_line() {
+    let mut cpu = cpu_at(0xC000, &[0x4C, 0xF5, 0xC5]);
+    cpu.status_register = 0x24;
+    cpu.cycles = 7;
+    assert_eq!(
+        trace_line(&cpu),
+        "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7"
+    );
+}
+
+#[test]
+fn test_unofficial_opcodes_are_starred() {
+    let mut cpu = cpu_at(0xC6BD, &[0x04, 0xA9]);
+    cpu.register_a = 0xAA;
+    assert!(trace_line(&cpu).starts_with("C6BD  04 A9    *NOP $A9 = 00                    A:AA"));
+}
+
+#[test]
+fn test_disassemble_simple_modes() {
+    let cpu = cpu_at(0x0600, &[0x18]);
+    assert_eq!(disassemble(&cpu, 0x0600), "CLC");
+    let cpu = cpu_at(0x0600, &[0x0A]);
+    assert_eq!(disassemble(&cpu, 0x0600), "ASL A");
+    let cpu = cpu_at(0x0600, &[0xA9, 0x7F]);
+    assert_eq!(disassemble(&cpu, 0x0600), "LDA #$7F");
+    let cpu = cpu_at(0x0600, &[0x20, 0x34, 0x12]);
+    assert_eq!(disassemble(&cpu, 0x0600), "JSR $1234");
+}
+
+#[test]
+fn test_disassemble_resolves_operands() {
+    let mut cpu = cpu_at(0x0600, &[0x95, 0x10]);
+    cpu.register_x = 0xF5;
+    cpu.mem_write(0x05, 0x42);
+    assert_eq!(disassemble(&cpu, 0x0600), "STA $10,X @ 05 = 42");
+
+    let mut cpu = cpu_at(0x0600, &[0xBD, 0xF0, 0x02]);
+    cpu.register_x = 0x20;
+    cpu.mem_write(0x0310, 0x99);
+    assert_eq!(disassemble(&cpu, 0x0600), "LDA $02F0,X @ 0310 = 99");
+
+    let mut cpu = cpu_at(0x0600, &[0xA1, 0x80]);
+    cpu.register_x = 2;
+    cpu.mem_write(0x82, 0x00);
+    cpu.mem_write(0x83, 0x03);
+    cpu.mem_write(0x0300, 0x5A);
+    assert_eq!(disassemble(&cpu, 0x0600), "LDA ($80,X) @ 82 = 0300 = 5A");
+}
+
+#[test]
+fn test_disassemble_pointer_wraps() {
+    let mut cpu = cpu_at(0x0600, &[0xA1, 0xFF]);
+    cpu.mem_write(0xFF, 0x34);
+    cpu.mem_write(0x00, 0x12);
+    cpu.mem_write(0x1234, 0x77);
+    assert_eq!(disassemble(&cpu, 0x0600), "LDA ($FF,X) @ FF = 1234 = 77");
+
+    cpu.mem_write(0x0600, 0xB1);
+    cpu.register_y = 1;
+    assert_eq!(disassemble(&cpu, 0x0600), "LDA ($FF),Y = 1234 @ 1235 = 00");
+
+    let mut cpu = cpu_at(0x0600, &[0x6C, 0xFF, 0x02]);
+    cpu.mem_write(0x02FF, 0x00);
+    cpu.mem_write(0x0200, 0x03);
+    cpu.mem_write(0x0300, 0x04);
+    assert_eq!(disassemble(&cpu, 0x0600), "JMP ($02FF) = 0300");
+}
+
+#[test]
+fn test_disassemble_branch_targets() {
+    let cpu = cpu_at(0x0600, &[0xD0, 0xFC]);
+    assert_eq!(disassemble(&cpu, 0x0600), "BNE $05FE");
+    let cpu = cpu_at(0x0600, &[0x10, 0x7F]);
+    assert_eq!(disassemble(&cpu, 0x0600), "BPL $0681");
+}
+
+#[test]
+fn test_ppu_position() {
+    assert_eq!(ppu_position(Region::Ntsc, 7), (0, 21));
+    assert_eq!(ppu_position(Region::Ntsc, 114), (1, 1));
+    // 89342 dots to a frame
+    assert_eq!(ppu_position(Region::Ntsc, 29781), (0, 1));
+    assert_eq!(ppu_position(Region::Pal, 5), (0, 16));
+}
+
+#[test]
+fn test_logger_follows_execution_and_toggles() {
+    let dir = TempDir::new("trace");
+    let path = dir.join("trace.log");
+    let mut cpu = CPU::new();
+    cpu.load_program(vec![0xa9, 0x05, 0xaa, 0xe8, 0x00]);
+    cpu.reset();
+    cpu.trace = Some(TraceLogger::to_file(&path).unwrap());
+
+    cpu.step();
+    cpu.step();
+    cpu.trace.as_mut().unwrap().set_enabled(false);
+    cpu.step();
+    cpu.trace.as_mut().unwrap().set_enabled(true);
+    cpu.step();
+    cpu.trace.take().unwrap().finish().unwrap();
+
+    let log = std::fs::read_to_string(&path).unwrap();
+    let pcs: Vec<&str> = log.lines().map(|line| &line[..4]).collect();
+    assert_eq!(pcs, ["8000", "8002", "8004"]);
+    assert!(log.lines().nth(1).unwrap().contains("TAX  "));
+    assert!(log
+        .lines()
+        .nth(1)
+        .unwrap()
+        .ends_with("A:05 X:00 Y:00 P:00 SP:FD PPU:  0,  6 CYC:2"));
+}
@@ -0,0 +1,87 @@
+use std::sync::{Arc, Mutex};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample, Stream, StreamConfig};
+
+use crate::apu::ring_buffer::RingBuffer;
+
+// a quarter of a second at 48 kHz
+const QUEUE_CAPACITY: usize = 12_000;
+
+// The default sound output, fed mono samples from the APU's resampler.
+pub struct AudioOutput {
+    _stream: Stream,
+    queue: Arc<Mutex<RingBuffer>>,
+    sample_rate: u32,
+}
+
+impl AudioOutput {
+    pub fn open() -> Result<AudioOutput, String> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or("no audio output device")?;
+        let supported = device
+            .default_output_config()
+            .map_err(|err| err.to_string())?;
+        let sample_rate = supported.sample_rate().0;
+        let format = supported.sample_format();
+        let config = supported.into();
+        let queue = Arc::new(Mutex::new(RingBuffer::new(QUEUE_CAPACITY)));
+        let stream = match format {
+            SampleFormat::F32 => build_stream::<f32>(&device, &config, queue.clone()),
+            SampleFormat::I16 => build_stream::<i16>(&device, &config, queue.clone()),
+            SampleFormat::U16 => build_stream::<u16>(&device, &config, queue.clone()),
+            other => Err(format!("unsupported sample format {}", other)),
+        }?;
+        stream.play().map_err(|err| err.to_string())?;
+        Ok(AudioOutput {
+            _stream: stream,
+            queue,
+            sample_rate,
+        })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn queued(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    pub fn push(&self, samples: &[f32]) {
+        let mut queue = self.queue.lock().unwrap();
+        for &sample in samples {
+            queue.push(sample);
+        }
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    queue: Arc<Mutex<RingBuffer>>,
+) -> Result<Stream, String>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let channels = config.channels as usize;
+    let mut last = 0.0;
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                let mut queue = queue.lock().unwrap();
+                for frame in data.chunks_mut(channels) {
+                    // on an underrun hold the last level rather than click
+                    if let Some(sample) = queue.pop() {
+                        last = sample;
+                    }
+                    frame.fill(T::from_sample(last));
+                }
+            },
+            |err| eprintln!("nesoxide: audio: {}", err),
+            None,
+        )
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::Path;
+
+use crate::input::controller::*;
+
+const BUTTON_NAMES: [(&str, u8); 8] = [
+    ("a", BUTTON_A),
+    ("b", BUTTON_B),
+    ("select", BUTTON_SELECT),
+    ("start", BUTTON_START),
+    ("up", BUTTON_UP),
+    ("down", BUTTON_DOWN),
+    ("left", BUTTON_LEFT),
+    ("right", BUTTON_RIGHT),
+];
+
+const DEFAULT_BINDINGS: &str = "
+key Up = 1 up
+key Down = 1 down
+key Left = 1 left
+key Right = 1 right
+key X = 1 a
+key Z = 1 b
+key Enter = 1 start
+key RightShift = 1 select
+pad1 DPadUp = 1 up
+pad1 DPadDown = 1 down
+pad1 DPadLeft = 1 left
+pad1 DPadRight = 1 right
+pad1 East = 1 a
+pad1 South = 1 b
+pad1 Start = 1 start
+pad1 Select = 1 select
+pad2 DPadUp = 2 up
+pad2 DPadDown = 2 down
+pad2 DPadLeft = 2 left
+pad2 DPadRight = 2 right
+pad2 East = 2 a
+pad2 South = 2 b
+pad2 Start = 2 start
+pad2 Select = 2 select
+";
+
+// Where a host input comes from: the keyboard or the nth gamepad (from 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Key,
+    Pad(usize),
+}
+
+// A held host input. Names are the backend's own key and button names, so
+// the bindings don't depend on any windowing library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    pub source: Source,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Binding {
+    input: Input,
+    player: usize,
+    button: u8,
+}
+
+// Maps host inputs to controller buttons. The text format has one binding
+// per line, `<key|padN> <input name> = <player> <button>`, and # comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bindings {
+    bindings: Vec<Binding>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self::parse(DEFAULT_BINDINGS).unwrap()
+    }
+}
+
+impl Bindings {
+    pub fn parse(text: &str) -> Result<Bindings, String> {
+        let mut bindings = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let binding =
+                parse_line(line).ok_or(format!("bindings line {}: {}", number + 1, line))?;
+            bindings.push(binding);
+        }
+        Ok(Bindings { bindings })
+    }
+
+    pub fn load(path: &Path) -> Result<Bindings, String> {
+        let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        Self::parse(&text)
+    }
+
+    // The buttons `player` is holding given every held host input.
+    pub fn buttons(&self, player: usize, held: &[Input]) -> u8 {
+        self.bindings
+            .iter()
+            .filter(|binding| binding.player == player)
+            .filter(|binding| {
+                held.iter().any(|input| {
+                    input.source == binding.input.source
+                        && input.name.eq_ignore_ascii_case(&binding.input.name)
+                })
+            })
+            .fold(0, |buttons, binding| buttons | binding.button)
+    }
+}
+
+fn parse_line(line: &str) -> Option<Binding> {
+    let (input, target) = line.split_once('=')?;
+    let mut input = input.split_whitespace();
+    let source = match input.next()? {
+        "key" => Source::Key,
+        pad => Source::Pad(pad.strip_prefix("pad")?.parse().ok().filter(|&n| n > 0)?),
+    };
+    let name = input.next()?.to_string();
+    let mut target = target.split_whitespace();
+    let player = target
+        .next()?
+        .parse()
+        .ok()
+        .filter(|n| (1..=4).contains(n))?;
+    let button_name = target.next()?;
+    let (_, button) = BUTTON_NAMES
+        .iter()
+        .find(|(button, _)| button.eq_ignore_ascii_case(button_name))?;
+    if input.next().is_some() || target.next().is_some() {
+        return None;
+    }
+    Some(Binding {
+        input: Input { source, name },
+        player,
+        button: *button,
+    })
+}
+
+#[cfg(test)]
+#[path = "./bindings_test.rs"]
+mod bindings_tests;
@@ -0,0 +1,60 @@
+use super::*;
+
+fn key(name: &str) -> Input {
+    Input {
+        source: Source::Key,
+        name: name.to_string(),
+    }
+}
+
+fn pad(index: usize, name: &str) -> Input {
+    Input {
+        source: Source::Pad(index),
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn test_default_bindings() {
+    let bindings = Bindings::default();
+    assert_eq!(
+        bindings.buttons(1, &[key("Up"), key("X"), key("Q")]),
+        BUTTON_UP | BUTTON_A
+    );
+    assert_eq!(
+        bindings.buttons(2, &[pad(2, "South"), pad(1, "Start")]),
+        BUTTON_B
+    );
+    assert_eq!(bindings.buttons(1, &[pad(1, "Start")]), BUTTON_START);
+    assert_eq!(bindings.buttons(3, &[key("X")]), 0);
+}
+
+#[test]
+fn test_parse_custom_bindings() {
+    let bindings = Bindings::parse(
+        "# player 2 on the keyboard\n\
+         key W = 2 up   # comment\n\
+         \n\
+         key space = 2 A\n\
+         pad3 Mode = 4 select\n",
+    )
+    .unwrap();
+    assert_eq!(
+        bindings.buttons(2, &[key("w"), key("Space")]),
+        BUTTON_UP | BUTTON_A
+    );
+    assert_eq!(bindings.buttons(4, &[pad(3, "Mode")]), BUTTON_SELECT);
+    assert_eq!(bindings.buttons(1, &[key("Up")]), 0);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(Bindings::parse("key W = 2 jump").is_err());
+    assert!(Bindings::parse("key W = 5 up").is_err());
+    assert!(Bindings::parse("mouse Left = 1 a").is_err());
+    assert!(Bindings::parse("pad0 South = 1 a").is_err());
+    assert!(Bindings::parse("key W 1 up").is_err());
+    assert!(Bindings::parse("key W = 1 up down").is_err());
+    let err = Bindings::parse("key A = 1 a\nkey W = 1").unwrap_err();
+    assert!(err.contains("line 2"));
+}
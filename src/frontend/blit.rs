@@ -0,0 +1,31 @@
+use crate::render::frame::Frame;
+use crate::render::frame::{HEIGHT, WIDTH};
+
+// Largest whole-number scale at which the frame fits the given area.
+pub fn fit_scale(width: usize, height: usize) -> usize {
+    (width / WIDTH).min(height / HEIGHT).max(1)
+}
+
+// Copies the frame into a 0RGB buffer of `width` x `height`, each pixel
+// repeated `scale` times both ways and the picture centred on black.
+pub fn blit(frame: &Frame, scale: usize, width: usize, height: usize, buffer: &mut Vec<u32>) {
+    buffer.clear();
+    buffer.resize(width * height, 0);
+    let left = width.saturating_sub(WIDTH * scale) / 2;
+    let top = height.saturating_sub(HEIGHT * scale) / 2;
+    let columns = (WIDTH * scale).min(width);
+    let rows = (HEIGHT * scale).min(height);
+
+    for row in 0..rows {
+        let y = row / scale;
+        let line = &mut buffer[(top + row) * width + left..][..columns];
+        for (column, pixel) in line.iter_mut().enumerate() {
+            let (r, g, b) = frame.pixel(column / scale, y);
+            *pixel = (r as u32) << 16 | (g as u32) << 8 | b as u32;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./blit_test.rs"]
+mod blit_tests;
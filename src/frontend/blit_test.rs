@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn test_fit_scale() {
+    assert_eq!(fit_scale(1920, 1080), 4);
+    assert_eq!(fit_scale(1280, 720), 3);
+    assert_eq!(fit_scale(100, 100), 1);
+}
+
+#[test]
+fn test_integer_scaling() {
+    let mut frame = Frame::new();
+    frame.set_pixel(0, 0, (0x12, 0x34, 0x56));
+    frame.set_pixel(WIDTH - 1, HEIGHT - 1, (0xFF, 0, 0));
+    let mut buffer = Vec::new();
+    blit(&frame, 2, WIDTH * 2, HEIGHT * 2, &mut buffer);
+    assert_eq!(buffer.len(), WIDTH * HEIGHT * 4);
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        assert_eq!(buffer[y * WIDTH * 2 + x], 0x0012_3456);
+    }
+    assert_eq!(buffer[2], 0);
+    assert_eq!(*buffer.last().unwrap(), 0x00FF_0000);
+}
+
+#[test]
+fn test_letterbox_centres_picture() {
+    let mut frame = Frame::new();
+    frame.set_pixel(0, 0, (0xFF, 0xFF, 0xFF));
+    let mut buffer = vec![0xDEAD; 3];
+    //a 1920x1080 screen fits scale 4: 1024x960, centred at (448, 60)
+    blit(&frame, 4, 1920, 1080, &mut buffer);
+    assert_eq!(buffer.len(), 1920 * 1080);
+    assert_eq!(buffer[60 * 1920 + 448], 0x00FF_FFFF);
+    assert_eq!(buffer[60 * 1920 + 447], 0);
+    assert_eq!(buffer[59 * 1920 + 448], 0);
+    assert_eq!(buffer[0], 0);
+}
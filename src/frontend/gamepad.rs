@@ -0,0 +1,52 @@
+use gilrs::{Button, Gilrs};
+
+use super::bindings::{Input, Source};
+
+const BUTTONS: [Button; 17] = [
+    Button::South,
+    Button::East,
+    Button::North,
+    Button::West,
+    Button::LeftTrigger,
+    Button::LeftTrigger2,
+    Button::RightTrigger,
+    Button::RightTrigger2,
+    Button::Select,
+    Button::Start,
+    Button::Mode,
+    Button::LeftThumb,
+    Button::RightThumb,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+];
+
+pub struct Gamepads {
+    gilrs: Gilrs,
+}
+
+impl Gamepads {
+    pub fn open() -> Result<Gamepads, String> {
+        let gilrs = Gilrs::new().map_err(|err| err.to_string())?;
+        Ok(Gamepads { gilrs })
+    }
+
+    // Held buttons on every connected pad, named like gilrs names them and
+    // with pads numbered from 1.
+    pub fn held(&mut self) -> Vec<Input> {
+        while self.gilrs.next_event().is_some() {}
+        let mut held = Vec::new();
+        for (index, (_, gamepad)) in self.gilrs.gamepads().enumerate() {
+            for button in BUTTONS {
+                if gamepad.is_pressed(button) {
+                    held.push(Input {
+                        source: Source::Pad(index + 1),
+                        name: format!("{:?}", button),
+                    });
+                }
+            }
+        }
+        held
+    }
+}
@@ -0,0 +1,11 @@
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod bindings;
+pub mod blit;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod pacer;
+#[cfg(unix)]
+pub mod terminal;
+#[cfg(feature = "window")]
+pub mod window;
@@ -0,0 +1,57 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::apu::Region;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pacing {
+    // emulate whenever the sound queue runs low, the sound card's clock sets
+    // the speed and audio never crackles
+    Audio,
+    // emulate one frame per frame time measured by a sleep timer, audio is
+    // dropped or stretched as it drifts. This doesn't sync to the display's
+    // refresh, so frames can tear or be shown twice.
+    Timer,
+}
+
+// Audio pacing: another frame is due while less than `latency` worth of
+// samples is queued for the sound card.
+pub fn audio_wants_frame(queued_samples: usize, sample_rate: u32, latency: Duration) -> bool {
+    (queued_samples as f64) < latency.as_secs_f64() * sample_rate as f64
+}
+
+// Sleeps between frames so emulation runs at the console's frame rate.
+pub struct FramePacer {
+    frame_time: Duration,
+    next_frame: Instant,
+}
+
+impl FramePacer {
+    pub fn new(region: Region) -> Self {
+        let (num, den) = region.frame_rate();
+        Self {
+            frame_time: Duration::from_secs_f64(den as f64 / num as f64),
+            next_frame: Instant::now(),
+        }
+    }
+
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    // Blocks until the next frame is due. When the host falls more than a
+    // frame behind the schedule restarts instead of trying to catch up.
+    pub fn wait(&mut self) {
+        self.next_frame += self.frame_time;
+        let now = Instant::now();
+        if self.next_frame > now {
+            thread::sleep(self.next_frame - now);
+        } else if now - self.next_frame > self.frame_time {
+            self.next_frame = now;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./pacer_test.rs"]
+mod pacer_tests;
@@ -0,0 +1,27 @@
+use super::*;
+
+#[test]
+fn test_frame_time() {
+    let micros = FramePacer::new(Region::Ntsc).frame_time().as_micros();
+    assert!((16_638..=16_640).contains(&micros), "{}", micros);
+    let micros = FramePacer::new(Region::Pal).frame_time().as_micros();
+    assert!((19_996..=19_998).contains(&micros), "{}", micros);
+}
+
+#[test]
+fn test_pacer_keeps_time() {
+    let mut pacer = FramePacer::new(Region::Ntsc);
+    let start = Instant::now();
+    for _ in 0..3 {
+        pacer.wait();
+    }
+    assert!(start.elapsed() >= pacer.frame_time() * 3);
+}
+
+#[test]
+fn test_audio_wants_frame() {
+    let latency = Duration::from_millis(50);
+    assert!(audio_wants_frame(0, 48_000, latency));
+    assert!(audio_wants_frame(2_399, 48_000, latency));
+    assert!(!audio_wants_frame(2_400, 48_000, latency));
+}
@@ -0,0 +1,208 @@
+use std::io::{self, Read, Write};
+use std::mem;
+
+use super::pacer::FramePacer;
+use crate::cpu::opcode_implementation::CPU;
+use crate::input::controller::*;
+use crate::render::frame::Frame;
+use crate::render::frame::{HEIGHT, WIDTH};
+
+// Terminals only report key presses, never releases, so a press holds the
+// button for a few frames and the terminal's key repeat keeps it held.
+const HOLD_FRAMES: u8 = 8;
+const DEFAULT_COLUMNS: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Button(u8),
+    Quit,
+}
+
+// Decodes raw terminal bytes: arrows drive the d-pad, x is A, z is B, Enter
+// is Start, Space is Select, q, Esc or Ctrl-C quits.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            // ESC [ A..D arrow keys, in application mode ESC O A..D
+            0x1B if i + 2 < bytes.len() && (bytes[i + 1] == b'[' || bytes[i + 1] == b'O') => {
+                match bytes[i + 2] {
+                    b'A' => keys.push(Key::Button(BUTTON_UP)),
+                    b'B' => keys.push(Key::Button(BUTTON_DOWN)),
+                    b'C' => keys.push(Key::Button(BUTTON_RIGHT)),
+                    b'D' => keys.push(Key::Button(BUTTON_LEFT)),
+                    _ => {}
+                }
+                i += 2;
+            }
+            0x1B | 0x03 | b'q' => keys.push(Key::Quit),
+            b'x' | b'X' => keys.push(Key::Button(BUTTON_A)),
+            b'z' | b'Z' => keys.push(Key::Button(BUTTON_B)),
+            b'\r' | b'\n' => keys.push(Key::Button(BUTTON_START)),
+            b' ' => keys.push(Key::Button(BUTTON_SELECT)),
+            _ => {}
+        }
+        i += 1;
+    }
+    keys
+}
+
+#[derive(Default)]
+pub struct HeldButtons {
+    // frames left for each button bit
+    frames: [u8; 8],
+}
+
+impl HeldButtons {
+    pub fn press(&mut self, button: u8) {
+        for (bit, frames) in self.frames.iter_mut().enumerate() {
+            if button & (1 << bit) != 0 {
+                *frames = HOLD_FRAMES;
+            }
+        }
+    }
+
+    // The buttons held this frame, counting the frame down.
+    pub fn next_frame(&mut self) -> u8 {
+        let mut buttons = 0;
+        for (bit, frames) in self.frames.iter_mut().enumerate() {
+            if *frames > 0 {
+                buttons |= 1 << bit;
+                *frames -= 1;
+            }
+        }
+        buttons
+    }
+}
+
+// Draws the frame `columns` characters wide. Each character cell shows two
+// pixels stacked vertically: the upper half block in the foreground colour
+// and the lower one in the background colour.
+pub fn render_frame(frame: &Frame, columns: usize, out: &mut Vec<u8>) {
+    let columns = columns.clamp(1, WIDTH);
+    let rows = (HEIGHT * columns / WIDTH).div_ceil(2);
+    out.extend_from_slice(b"\x1b[H");
+    let mut last = None;
+    for row in 0..rows {
+        for column in 0..columns {
+            let x = column * WIDTH / columns;
+            let top = (row * 2 * WIDTH / columns).min(HEIGHT - 1);
+            let bottom = ((row * 2 + 1) * WIDTH / columns).min(HEIGHT - 1);
+            let colours = (frame.pixel(x, top), frame.pixel(x, bottom));
+            // only send colours when they change, most of a frame is flat
+            if last != Some(colours) {
+                let ((fr, fg, fb), (br, bg, bb)) = colours;
+                write!(
+                    out,
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
+                    fr, fg, fb, br, bg, bb
+                )
+                .unwrap();
+                last = Some(colours);
+            }
+            out.extend_from_slice("▀".as_bytes());
+        }
+        out.extend_from_slice(b"\x1b[0m\r\n");
+        last = None;
+    }
+}
+
+// Puts the terminal into raw, non-blocking mode on an alternate screen and
+// puts everything back when dropped, including while unwinding.
+struct RawTerminal {
+    original: libc::termios,
+}
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        // SAFETY: termios is plain C data, all zeroes is a valid value.
+        let mut original: libc::termios = unsafe { mem::zeroed() };
+        // SAFETY: the pointer is to a live termios for tcgetattr to fill in.
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut raw = original;
+        // SAFETY: cfmakeraw only changes flags in the termios it is given.
+        unsafe { libc::cfmakeraw(&mut raw) };
+        // reads return straight away with whatever is buffered
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: raw is an initialised termios that tcsetattr only reads.
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut stdout = io::stdout();
+        stdout.write_all(b"\x1b[?1049h\x1b[?25l\x1b[2J")?;
+        stdout.flush()?;
+        Ok(Self { original })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = stdout.flush();
+        // The result is ignored: drop can't report it and this may run while
+        // unwinding. If stdin is gone there is no terminal left to restore.
+        // SAFETY: original is the termios tcgetattr filled in, only read here.
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
+
+fn terminal_columns() -> usize {
+    // SAFETY: winsize is plain C data, all zeroes is a valid value.
+    let mut size: libc::winsize = unsafe { mem::zeroed() };
+    // SAFETY: TIOCGWINSZ writes a winsize through the pointer, which is to
+    // a live one.
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0
+        || size.ws_col == 0
+    {
+        return DEFAULT_COLUMNS;
+    }
+    // keep the picture's aspect when the terminal is short
+    let by_height = (size.ws_row as usize).saturating_sub(1) * 2 * WIDTH / HEIGHT;
+    (size.ws_col as usize).min(by_height).max(1)
+}
+
+// Plays until the user quits, drawing to stdout and reading keys from stdin.
+// The picture stays black until a PPU draws into `cpu.frame`.
+pub fn run(cpu: &mut CPU) -> io::Result<()> {
+    let _terminal = RawTerminal::enter()?;
+    let mut stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut pacer = FramePacer::new(cpu.apu.region());
+    let frame_cycles = cpu.apu.region().cpu_cycles_per_frame();
+    let start = cpu.cycles;
+    let mut held = HeldButtons::default();
+    let mut input = [0u8; 64];
+    let mut screen = Vec::new();
+
+    for frame in 1.. {
+        let count = stdin.read(&mut input)?;
+        for key in parse_keys(&input[..count]) {
+            match key {
+                Key::Quit => return Ok(()),
+                Key::Button(button) => held.press(button),
+            }
+        }
+        let buttons = held.next_frame();
+        if let Some(controller) = cpu.input.controller(1) {
+            controller.set_buttons(buttons);
+        }
+
+        cpu.run_until(start + (frame as f64 * frame_cycles) as usize);
+
+        screen.clear();
+        render_frame(&cpu.frame, terminal_columns(), &mut screen);
+        stdout.write_all(&screen)?;
+        stdout.flush()?;
+        pacer.wait();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./terminal_test.rs"]
+mod terminal_tests;
@@ -0,0 +1,61 @@
+use super::*;
+
+#[test]
+fn test_parse_keys() {
+    assert_eq!(
+        parse_keys(b"\x1b[A\x1b[Dxz\r "),
+        vec![
+            Key::Button(BUTTON_UP),
+            Key::Button(BUTTON_LEFT),
+            Key::Button(BUTTON_A),
+            Key::Button(BUTTON_B),
+            Key::Button(BUTTON_START),
+            Key::Button(BUTTON_SELECT),
+        ]
+    );
+    assert_eq!(parse_keys(b"\x1bOB"), vec![Key::Button(BUTTON_DOWN)]);
+    assert_eq!(parse_keys(b"q"), vec![Key::Quit]);
+    assert_eq!(parse_keys(b"\x03"), vec![Key::Quit]);
+    //a lone escape is the Esc key
+    assert_eq!(parse_keys(b"\x1b"), vec![Key::Quit]);
+    assert_eq!(parse_keys(b"k"), vec![]);
+}
+
+#[test]
+fn test_held_buttons_release_after_hold() {
+    let mut held = HeldButtons::default();
+    held.press(BUTTON_A);
+    for _ in 0..HOLD_FRAMES {
+        assert_eq!(held.next_frame(), BUTTON_A);
+    }
+    assert_eq!(held.next_frame(), 0);
+    held.press(BUTTON_UP);
+    held.press(BUTTON_B);
+    assert_eq!(held.next_frame(), BUTTON_UP | BUTTON_B);
+}
+
+#[test]
+fn test_render_half_blocks() {
+    let mut frame = Frame::new();
+    frame.set_pixel(0, 0, (255, 0, 0));
+    frame.set_pixel(0, 1, (0, 0, 255));
+    let mut out = Vec::new();
+    render_frame(&frame, WIDTH, &mut out);
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.starts_with("\x1b[H\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m▀"));
+    //120 rows of 256 cells
+    assert_eq!(text.matches("\r\n").count(), HEIGHT / 2);
+    assert_eq!(text.matches('▀').count(), WIDTH * HEIGHT / 2);
+    //the rest of the first row is black and only coloured once
+    let first_row = text.split("\r\n").next().unwrap();
+    assert_eq!(first_row.matches("\x1b[38;2;").count(), 2);
+}
+
+#[test]
+fn test_render_scaled_down() {
+    let mut out = Vec::new();
+    render_frame(&Frame::new(), 64, &mut out);
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.matches("\r\n").count(), 30);
+    assert_eq!(text.matches('▀').count(), 64 * 30);
+}
@@ -0,0 +1,199 @@
+use std::thread;
+use std::time::Duration;
+
+use minifb::{Key, Window, WindowOptions};
+
+#[cfg(feature = "audio")]
+use super::audio::AudioOutput;
+use super::bindings::{Bindings, Input, Source};
+use super::blit;
+#[cfg(feature = "gamepad")]
+use super::gamepad::Gamepads;
+use super::pacer::{self, FramePacer, Pacing};
+use crate::cpu::opcode_implementation::CPU;
+use crate::render::frame::{HEIGHT, WIDTH};
+
+// how much sound to keep queued when pacing by audio
+const AUDIO_LATENCY: Duration = Duration::from_millis(50);
+const PLAYERS: usize = 4;
+
+pub struct WindowSettings {
+    pub scale: usize,
+    // a borderless window covering the screen, the picture at the largest
+    // integer scale that fits
+    pub fullscreen: bool,
+    pub bindings: Bindings,
+    pub pacing: Pacing,
+    pub audio: bool,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            scale: 3,
+            fullscreen: false,
+            bindings: Bindings::default(),
+            pacing: Pacing::Audio,
+            audio: true,
+        }
+    }
+}
+
+// Plays in a desktop window until it is closed or Escape is pressed.
+// The picture stays black until a PPU draws into `cpu.frame`.
+pub fn run(cpu: &mut CPU, settings: WindowSettings) -> Result<(), String> {
+    let (width, height, scale) = match settings.fullscreen.then(screen_size).flatten() {
+        Some((width, height)) => (width, height, blit::fit_scale(width, height)),
+        None => (
+            WIDTH * settings.scale,
+            HEIGHT * settings.scale,
+            settings.scale,
+        ),
+    };
+    let options = WindowOptions {
+        borderless: settings.fullscreen,
+        title: !settings.fullscreen,
+        topmost: settings.fullscreen,
+        ..WindowOptions::default()
+    };
+    let mut window =
+        Window::new("nesoxide", width, height, options).map_err(|err| err.to_string())?;
+    // pacing is ours, don't let minifb sleep as well
+    window.set_target_fps(0);
+
+    let audio = if settings.audio { open_audio() } else { None };
+    let pacing = match (&audio, settings.pacing) {
+        (None, Pacing::Audio) => Pacing::Timer,
+        (_, pacing) => pacing,
+    };
+    if let Some(audio) = &audio {
+        cpu.apu.set_sample_rate(audio.sample_rate());
+    }
+    let mut gamepads = open_gamepads();
+
+    let mut pacer = FramePacer::new(cpu.apu.region());
+    let frame_cycles = cpu.apu.region().cpu_cycles_per_frame();
+    let start = cpu.cycles;
+    let mut samples = vec![0f32; cpu.apu.resampler.samples.capacity()];
+    let mut buffer = Vec::new();
+    let mut frame = 0;
+
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        if let (Pacing::Audio, Some(audio)) = (pacing, &audio) {
+            if !pacer::audio_wants_frame(audio.queued(), audio.sample_rate(), AUDIO_LATENCY) {
+                window.update();
+                thread::sleep(Duration::from_millis(1));
+                continue;
+            }
+        }
+
+        let mut held: Vec<Input> = window
+            .get_keys()
+            .into_iter()
+            .map(|key| Input {
+                source: Source::Key,
+                name: format!("{:?}", key),
+            })
+            .collect();
+        if let Some(gamepads) = gamepads.as_mut() {
+            held.extend(gamepads.held());
+        }
+        for player in 1..=PLAYERS {
+            let buttons = settings.bindings.buttons(player, &held);
+            if let Some(controller) = cpu.input.controller(player) {
+                controller.set_buttons(buttons);
+            }
+        }
+
+        frame += 1;
+        cpu.run_until(start + (frame as f64 * frame_cycles) as usize);
+
+        let count = cpu.apu.pull_samples_f32(&mut samples);
+        if let Some(audio) = &audio {
+            audio.push(&samples[..count]);
+        }
+
+        blit::blit(&cpu.frame, scale, width, height, &mut buffer);
+        window
+            .update_with_buffer(&buffer, width, height)
+            .map_err(|err| err.to_string())?;
+        if pacing == Pacing::Timer {
+            pacer.wait();
+        }
+    }
+    Ok(())
+}
+
+fn open_audio() -> Option<AudioOutput> {
+    AudioOutput::open()
+        .map_err(|err| eprintln!("nesoxide: no sound: {}", err))
+        .ok()
+}
+
+fn open_gamepads() -> Option<Gamepads> {
+    Gamepads::open()
+        .map_err(|err| eprintln!("nesoxide: no gamepads: {}", err))
+        .ok()
+}
+
+// Stand-ins when built without sound or gamepad support. They can't be
+// constructed, so the frontend always sees None.
+#[cfg(not(feature = "audio"))]
+enum AudioOutput {}
+
+#[cfg(not(feature = "audio"))]
+impl AudioOutput {
+    fn open() -> Result<AudioOutput, String> {
+        Err("built without the audio feature".to_string())
+    }
+
+    fn sample_rate(&self) -> u32 {
+        match *self {}
+    }
+
+    fn queued(&self) -> usize {
+        match *self {}
+    }
+
+    fn push(&self, _samples: &[f32]) {
+        match *self {}
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+enum Gamepads {}
+
+#[cfg(not(feature = "gamepad"))]
+impl Gamepads {
+    fn open() -> Result<Gamepads, String> {
+        Err("built without the gamepad feature".to_string())
+    }
+
+    fn held(&mut self) -> Vec<Input> {
+        match *self {}
+    }
+}
+
+// The X11 screen size, used for fullscreen.
+#[cfg(target_os = "linux")]
+fn screen_size() -> Option<(usize, usize)> {
+    let xlib = x11_dl::xlib::Xlib::open().ok()?;
+    // SAFETY: the display is checked for null and closed before returning.
+    unsafe {
+        let display = (xlib.XOpenDisplay)(std::ptr::null());
+        if display.is_null() {
+            return None;
+        }
+        let screen = (xlib.XDefaultScreen)(display);
+        let width = (xlib.XDisplayWidth)(display, screen);
+        let height = (xlib.XDisplayHeight)(display, screen);
+        (xlib.XCloseDisplay)(display);
+        Some((width as usize, height as usize))
+    }
+}
+
+// elsewhere fullscreen falls back to the windowed scale
+#[cfg(not(target_os = "linux"))]
+fn screen_size() -> Option<(usize, usize)> {
+    None
+}
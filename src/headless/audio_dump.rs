@@ -0,0 +1,70 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::apu::wav::WavWriter;
+use crate::apu::CHANNEL_NAMES;
+use crate::cpu::opcode_implementation::CPU;
+
+// Runs the CPU for `frames` video frames and writes the mixed APU output to
+// `path` as 16-bit PCM. With `split_channels` every channel is also written
+// next to it, e.g. out.wav gets out-pulse1.wav, out-triangle.wav and so on.
+pub fn dump_audio(
+    cpu: &mut CPU,
+    frames: usize,
+    path: &Path,
+    split_channels: bool,
+) -> io::Result<()> {
+    // capture is switched off again however the dump ends
+    if split_channels {
+        cpu.apu.set_channel_capture(true);
+    }
+    let result = write_dump(cpu, frames, path, split_channels);
+    if split_channels {
+        cpu.apu.set_channel_capture(false);
+    }
+    result
+}
+
+fn write_dump(cpu: &mut CPU, frames: usize, path: &Path, split_channels: bool) -> io::Result<()> {
+    let sample_rate = cpu.apu.resampler.sample_rate();
+    let mut mixed = WavWriter::new(BufWriter::new(File::create(path)?), sample_rate)?;
+    let mut channels = Vec::new();
+    if split_channels {
+        for name in CHANNEL_NAMES {
+            let file = File::create(channel_path(path, name))?;
+            channels.push(WavWriter::new(BufWriter::new(file), sample_rate)?);
+        }
+    }
+    // anything queued before the dump started isn't part of it
+    cpu.apu.resampler.samples.clear();
+
+    let frame_cycles = cpu.apu.region().cpu_cycles_per_frame();
+    let start = cpu.cycles;
+    let mut buffer = vec![0i16; cpu.apu.resampler.samples.capacity()];
+    for frame in 1..=frames {
+        cpu.run_until(start + (frame as f64 * frame_cycles) as usize);
+
+        let count = cpu.apu.pull_samples_i16(&mut buffer);
+        mixed.write_samples(&buffer[..count])?;
+        for (channel, writer) in channels.iter_mut().enumerate() {
+            let count = cpu.apu.pull_channel_samples_i16(channel, &mut buffer);
+            writer.write_samples(&buffer[..count])?;
+        }
+    }
+
+    mixed.finish()?;
+    for writer in channels {
+        writer.finish()?;
+    }
+    Ok(())
+}
+
+pub fn channel_path(path: &Path, channel: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}-{}.wav", stem, channel))
+}
+
+#[cfg(test)]
+#[path = "./audio_dump_test.rs"]
+mod audio_dump_tests;
@@ -0,0 +1,73 @@
+use super::*;
+use crate::headless::TempDir;
+use std::fs;
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn tone_cpu() -> CPU {
+    let mut cpu = CPU::new();
+    cpu.load_program(vec![0x00]);
+    cpu.reset();
+    cpu.apu.write_register(0x4015, 0b0000_0001);
+    cpu.apu.write_register(0x4000, 0b1011_1111);
+    cpu.apu.write_register(0x4002, 0xFD);
+    cpu.apu.write_register(0x4003, 0b0000_1000);
+    cpu
+}
+
+#[test]
+fn test_dump_mixed_output() {
+    let dir = TempDir::new("wav");
+    let path = dir.join("mixed.wav");
+
+    let mut cpu = tone_cpu();
+    dump_audio(&mut cpu, 60, &path, false).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    assert_eq!(&bytes[0..4], b"RIFF");
+    assert_eq!(&bytes[8..12], b"WAVE");
+    assert_eq!(read_u32(&bytes, 24), 44_100);
+    let data_size = read_u32(&bytes, 40) as usize;
+    assert_eq!(read_u32(&bytes, 4) as usize, data_size + 36);
+    assert_eq!(bytes.len(), data_size + 44);
+    //one second of NTSC frames at 44.1 kHz
+    assert!((data_size / 2).abs_diff(44_100 * 60 * 29_781 / 1_789_773) <= 2);
+    assert!(!channel_path(&path, "pulse1").exists());
+}
+
+#[test]
+fn test_dump_split_channels() {
+    let dir = TempDir::new("split");
+    let path = dir.join("out.wav");
+
+    let mut cpu = tone_cpu();
+    dump_audio(&mut cpu, 10, &path, true).unwrap();
+    let mixed = fs::read(&path).unwrap();
+    let pulse1 = fs::read(dir.join("out-pulse1.wav")).unwrap();
+    let pulse2 = fs::read(dir.join("out-pulse2.wav")).unwrap();
+    assert_eq!(mixed.len(), pulse1.len());
+    assert_eq!(mixed.len(), pulse2.len());
+    //only pulse 1 is playing
+    let loudest = pulse1[44..]
+        .chunks(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]).unsigned_abs())
+        .max();
+    assert!(loudest > Some(1000));
+    assert!(pulse2[44..].iter().all(|&b| b == 0));
+    for name in ["triangle", "noise", "dmc"] {
+        assert!(dir.join(&format!("out-{}.wav", name)).exists());
+    }
+}
+
+#[test]
+fn test_failed_dump_stops_channel_capture() {
+    let dir = TempDir::new("missing");
+    let path = dir.join("missing").join("out.wav");
+
+    let mut cpu = tone_cpu();
+    assert!(dump_audio(&mut cpu, 10, &path, true).is_err());
+    cpu.run_until(cpu.cycles + 29_781);
+    let mut buffer = [0i16; 1024];
+    assert_eq!(cpu.apu.pull_channel_samples_i16(0, &mut buffer), 0);
+}
@@ -0,0 +1,50 @@
+pub mod audio_dump;
+pub mod nestest;
+pub mod output;
+pub mod test_rom;
+pub mod trap;
+pub mod video_dump;
+
+// Third-party test ROMs aren't kept in the repository, so the tests that need
+// them are ignored by default. Put the files in $NESOXIDE_TEST_ROMS, or
+// test-roms/ next to Cargo.toml, and run `cargo test -- --ignored`.
+#[cfg(test)]
+pub(crate) fn test_roms_dir() -> std::path::PathBuf {
+    match std::env::var_os("NESOXIDE_TEST_ROMS") {
+        Some(dir) => dir.into(),
+        None => std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test-roms"),
+    }
+}
+
+// A file from the test ROM directory. A missing one fails the test, which
+// was asked for by name.
+#[cfg(test)]
+pub(crate) fn read_test_file(name: &str) -> Vec<u8> {
+    let path = test_roms_dir().join(name);
+    std::fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+// A scratch directory under the system temp dir, removed again on drop.
+// `name` keeps tests running in parallel apart.
+#[cfg(test)]
+pub(crate) struct TempDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("nesoxide-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    pub(crate) fn join(&self, file: &str) -> std::path::PathBuf {
+        self.0.join(file)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
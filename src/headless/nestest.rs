@@ -0,0 +1,106 @@
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::cpu::opcode_implementation::CPU;
+use crate::cpu::trace;
+
+// nestest.nes can run without a PPU: started at $C000 instead of its reset
+// vector it goes through every test on its own, leaving an error code for
+// the official opcodes in $02 and for the unofficial ones in $03, 0 if all
+// of them passed.
+pub const AUTOMATION_START: u16 = 0xC000;
+const OFFICIAL_RESULT_ADDR: u16 = 0x02;
+const UNOFFICIAL_RESULT_ADDR: u16 = 0x03;
+// matching lines shown before a divergence
+const CONTEXT_LINES: usize = 5;
+
+// Puts the CPU in the state the reference log starts from, the reset
+// sequence's seven cycles included.
+pub fn start_automation(cpu: &mut CPU) {
+    cpu.program_counter = AUTOMATION_START;
+    cpu.status_register = 0x24;
+    cpu.stack_pointer = 0xFD;
+    cpu.cycles = 7;
+}
+
+// The first trace line that differs from the reference log.
+#[derive(Debug)]
+pub struct Divergence {
+    // 1-based line number in the reference log
+    pub line: usize,
+    // the lines leading up to it, which did match
+    pub context: Vec<String>,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "trace diverges from the reference at line {}:",
+            self.line
+        )?;
+        for line in &self.context {
+            writeln!(f, "           {}", line)?;
+        }
+        writeln!(f, "expected:  {}", self.expected)?;
+        write!(f, "actual:    {}", self.actual)
+    }
+}
+
+// Executes one instruction per line of `reference`, checking the trace line
+// before each. Returns how many lines matched, which is all of them.
+pub fn compare_with_log(cpu: &mut CPU, reference: &str) -> Result<usize, Divergence> {
+    let lines: Vec<&str> = reference.lines().map(str::trim_end).collect();
+    for (i, expected) in lines.iter().enumerate() {
+        let actual = trace::trace_line(cpu);
+        let context = || {
+            lines[i.saturating_sub(CONTEXT_LINES)..i]
+                .iter()
+                .map(|line| line.to_string())
+                .collect()
+        };
+        if actual != *expected {
+            return Err(Divergence {
+                line: i + 1,
+                context: context(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+        let failure = match panic::catch_unwind(AssertUnwindSafe(|| cpu.step())) {
+            Err(cause) => {
+                let message = cause
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| cause.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                Some(format!("crashed executing it: {}", message))
+            }
+            Ok(_) if cpu.jammed => Some("jammed the CPU".to_string()),
+            Ok(_) => None,
+        };
+        if let Some(actual) = failure {
+            return Err(Divergence {
+                line: i + 1,
+                context: context(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+    Ok(lines.len())
+}
+
+// The ($02, $03) error codes.
+pub fn result_codes(cpu: &CPU) -> (u8, u8) {
+    (
+        cpu.peek(OFFICIAL_RESULT_ADDR),
+        cpu.peek(UNOFFICIAL_RESULT_ADDR),
+    )
+}
+
+#[cfg(test)]
+#[path = "./nestest_test.rs"]
+mod nestest_tests;
@@ -0,0 +1,61 @@
+use super::*;
+use crate::cartridge::Rom;
+use crate::headless::read_test_file;
+
+const PROGRAM_LOG: &str = "\
+C000  A9 01     LDA #$01                        A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+C002  A2 02     LDX #$02                        A:01 X:00 Y:00 P:24 SP:FD PPU:  0, 27 CYC:9
+C004  E8        INX                             A:01 X:02 Y:00 P:24 SP:FD PPU:  0, 33 CYC:11
+";
+
+fn cpu_with_program(program: &[u8]) -> CPU {
+    let mut cpu = CPU::new();
+    for (i, &byte) in program.iter().enumerate() {
+        cpu.mem_write(AUTOMATION_START + i as u16, byte);
+    }
+    start_automation(&mut cpu);
+    cpu
+}
+
+#[test]
+fn test_matching_log() {
+    let mut cpu = cpu_with_program(&[0xa9, 0x01, 0xa2, 0x02, 0xe8]);
+    assert_eq!(compare_with_log(&mut cpu, PROGRAM_LOG).unwrap(), 3);
+    assert_eq!(cpu.register_x, 3);
+}
+
+#[test]
+fn test_reports_first_divergence() {
+    let mut cpu = cpu_with_program(&[0xa9, 0x01, 0xa2, 0x03, 0xe8]);
+    let divergence = compare_with_log(&mut cpu, PROGRAM_LOG).unwrap_err();
+    assert_eq!(divergence.line, 2);
+    assert_eq!(divergence.context, [PROGRAM_LOG.lines().next().unwrap()]);
+    assert!(divergence.actual.starts_with("C002  A2 03     LDX #$03"));
+    let report = divergence.to_string();
+    assert!(report.starts_with("trace diverges from the reference at line 2:\n"));
+    assert!(report.contains("expected:  C002  A2 02"));
+}
+
+#[test]
+fn test_reports_jams() {
+    let mut cpu = cpu_with_program(&[0x02]);
+    let log = trace::trace_line(&cpu);
+    let divergence = compare_with_log(&mut cpu, &log).unwrap_err();
+    assert_eq!(divergence.line, 1);
+    assert_eq!(divergence.expected, log);
+    assert_eq!(divergence.actual, "jammed the CPU");
+}
+
+#[test]
+#[ignore = "needs nestest.nes and nestest.log, see test_roms_dir"]
+fn test_nestest_rom() {
+    let raw = read_test_file("nestest.nes");
+    let reference = String::from_utf8(read_test_file("nestest.log")).unwrap();
+    let mut cpu = CPU::new();
+    cpu.load_rom(&Rom::new(&raw).unwrap()).unwrap();
+    start_automation(&mut cpu);
+    if let Err(divergence) = compare_with_log(&mut cpu, &reference) {
+        panic!("{}", divergence);
+    }
+    assert_eq!(result_codes(&cpu), (0, 0));
+}
@@ -0,0 +1,98 @@
+use crate::cpu::opcode_implementation::CPU;
+
+// Test ROMs following the blargg convention report through cartridge RAM:
+// a status byte at $6000, a signature right after it once that is valid and
+// a NUL-terminated text log from $6004.
+pub const STATUS_ADDR: u16 = 0x6000;
+const SIGNATURE_ADDR: u16 = 0x6001;
+const SIGNATURE: [u8; 3] = [0xDE, 0xB0, 0x61];
+const TEXT_ADDR: u16 = 0x6004;
+const TEXT_END: u16 = 0x8000;
+const STATUS_RUNNING: u8 = 0x80;
+// the ROM wants the reset button pressed, no sooner than 100 ms from now
+const STATUS_RESET_REQUIRED: u8 = 0x81;
+const RESET_DELAY_FRAMES: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestResult {
+    Passed,
+    Failed(u8),
+    // the ROM stopped or ran out of time without reporting anything
+    NoResult,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestReport {
+    pub result: TestResult,
+    // what the ROM printed, usually the test name and why it failed
+    pub text: String,
+}
+
+// Runs for up to `max_frames` frames, checking the status after each one
+// and pressing reset whenever the ROM asks for it.
+pub fn run_test_rom(cpu: &mut CPU, max_frames: usize) -> TestReport {
+    let frame_cycles = cpu.apu.region().cpu_cycles_per_frame();
+    let start = cpu.cycles;
+    let mut reset_frame = None;
+    for frame in 1..=max_frames {
+        let running = cpu.run_until(start + (frame as f64 * frame_cycles) as usize);
+        if let Some(result) = read_result(cpu) {
+            return TestReport {
+                result,
+                text: read_text(cpu),
+            };
+        }
+        if !running {
+            break;
+        }
+        match reset_frame {
+            Some(reset) if frame >= reset => {
+                cpu.soft_reset();
+                reset_frame = None;
+            }
+            None if has_signature(cpu) && cpu.peek(STATUS_ADDR) == STATUS_RESET_REQUIRED => {
+                reset_frame = Some(frame + RESET_DELAY_FRAMES);
+            }
+            _ => {}
+        }
+    }
+    TestReport {
+        result: TestResult::NoResult,
+        text: read_text(cpu),
+    }
+}
+
+pub fn read_result(cpu: &CPU) -> Option<TestResult> {
+    if !has_signature(cpu) {
+        return None;
+    }
+    match cpu.peek(STATUS_ADDR) {
+        0 => Some(TestResult::Passed),
+        status if status < STATUS_RUNNING => Some(TestResult::Failed(status)),
+        _ => None,
+    }
+}
+
+// The text written so far, empty until the signature is there.
+pub fn read_text(cpu: &CPU) -> String {
+    if !has_signature(cpu) {
+        return String::new();
+    }
+    let bytes: Vec<u8> = (TEXT_ADDR..TEXT_END)
+        .map(|addr| cpu.peek(addr))
+        .take_while(|&byte| byte != 0)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn has_signature(cpu: &CPU) -> bool {
+    [
+        cpu.peek(SIGNATURE_ADDR),
+        cpu.peek(SIGNATURE_ADDR + 1),
+        cpu.peek(SIGNATURE_ADDR + 2),
+    ] == SIGNATURE
+}
+
+#[cfg(test)]
+#[path = "./test_rom_test.rs"]
+mod test_rom_tests;
@@ -0,0 +1,76 @@
+use super::*;
+
+fn cpu_with_status(status: u8, signature: [u8; 3]) -> CPU {
+    let mut cpu = CPU::new();
+    cpu.load_program(vec![0x00]);
+    cpu.reset();
+    cpu.mem_write(STATUS_ADDR, status);
+    for (offset, byte) in signature.iter().enumerate() {
+        cpu.mem_write(SIGNATURE_ADDR + offset as u16, *byte);
+    }
+    cpu
+}
+
+#[test]
+fn test_passed_and_failed() {
+    assert_eq!(
+        run_test_rom(&mut cpu_with_status(0, SIGNATURE), 5).result,
+        TestResult::Passed
+    );
+    assert_eq!(
+        run_test_rom(&mut cpu_with_status(3, SIGNATURE), 5).result,
+        TestResult::Failed(3)
+    );
+}
+
+#[test]
+fn test_no_result_without_signature() {
+    let mut cpu = cpu_with_status(0, [0xDE, 0xB0, 0x00]);
+    assert_eq!(run_test_rom(&mut cpu, 5).result, TestResult::NoResult);
+    //still running when the time runs out
+    let mut cpu = cpu_with_status(STATUS_RUNNING, SIGNATURE);
+    assert_eq!(run_test_rom(&mut cpu, 5).result, TestResult::NoResult);
+}
+
+#[test]
+fn test_reads_text() {
+    let mut cpu = cpu_with_status(2, SIGNATURE);
+    for (offset, byte) in b"BIT\n\nFailed\n\0junk".iter().enumerate() {
+        cpu.mem_write(TEXT_ADDR + offset as u16, *byte);
+    }
+    assert_eq!(
+        run_test_rom(&mut cpu, 5),
+        TestReport {
+            result: TestResult::Failed(2),
+            text: "BIT\n\nFailed\n".to_string()
+        }
+    );
+    //nothing without the signature
+    cpu.mem_write(SIGNATURE_ADDR, 0);
+    assert_eq!(read_text(&cpu), "");
+}
+
+#[test]
+fn test_reset_when_asked() {
+    let mut program = vec![
+        0x78, //SEI
+        0xee, 0x10, 0x60, //INC $6010, counts the starts
+        0xa9, 0xde, 0x8d, 0x01, 0x60, //LDA #$DE; STA $6001
+        0xa9, 0xb0, 0x8d, 0x02, 0x60, //LDA #$B0; STA $6002
+        0xa9, 0x61, 0x8d, 0x03, 0x60, //LDA #$61; STA $6003
+        0xae, 0x10, 0x60, //LDX $6010
+        0xbd, 0x30, 0x80, //LDA $8030,X
+        0x8d, 0x00, 0x60, //STA $6000
+        0x4c, 0x1c, 0x80, //JMP *
+    ];
+    //$81 after the first start, passed after the second
+    program.resize(0x30, 0);
+    program.extend([0x00, STATUS_RESET_REQUIRED, 0x00]);
+    let mut cpu = CPU::new();
+    cpu.load_program(program);
+    cpu.reset();
+    assert_eq!(run_test_rom(&mut cpu, 3).result, TestResult::NoResult);
+    assert_eq!(cpu.peek(0x6010), 1);
+    assert_eq!(run_test_rom(&mut cpu, 10).result, TestResult::Passed);
+    assert_eq!(cpu.peek(0x6010), 2);
+}
@@ -0,0 +1,41 @@
+use crate::cpu::opcode_implementation::CPU;
+
+// Klaus Dormann's 6502 functional and decimal tests signal their result by
+// getting stuck: every check ends in a jump or branch to itself, a "trap".
+// The success trap is at a known address, any other means the check just
+// before it failed. They are flat 64 KiB images run as a generic 6502.
+pub const FUNCTIONAL_TEST_START: u16 = 0x0400;
+// as assembled with the default configuration
+pub const FUNCTIONAL_TEST_SUCCESS: u16 = 0x3469;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapResult {
+    Passed(u16),
+    // the source listing tells what was being tested at that address
+    Failed(u16),
+    // no trap within the cycle budget
+    TimedOut,
+}
+
+// Runs until an instruction leaves the program counter where it was, or
+// jams the CPU, for at most `max_cycles` cycles. BRK is just another
+// instruction here, the functional test checks it.
+pub fn run_until_trap(cpu: &mut CPU, success_pc: u16, max_cycles: usize) -> TrapResult {
+    let end = cpu.cycles + max_cycles;
+    while cpu.cycles < end {
+        let pc = cpu.program_counter;
+        cpu.step();
+        if cpu.jammed || cpu.program_counter == pc {
+            return if pc == success_pc {
+                TrapResult::Passed(pc)
+            } else {
+                TrapResult::Failed(pc)
+            };
+        }
+    }
+    TrapResult::TimedOut
+}
+
+#[cfg(test)]
+#[path = "./trap_test.rs"]
+mod trap_tests;
@@ -0,0 +1,84 @@
+use super::*;
+use crate::cpu::opcode_implementation::Machine;
+use crate::headless::read_test_file;
+
+// the decimal test leaves 0 here when every result was right
+const DECIMAL_TEST_ERROR: u16 = 0x000B;
+const DECIMAL_TEST_START: u16 = 0x0200;
+
+fn generic_cpu(image: &[u8], addr: u16) -> CPU {
+    let mut cpu = CPU::new();
+    cpu.machine = Machine::Generic6502;
+    cpu.load_image(image, addr).unwrap();
+    cpu.program_counter = addr;
+    cpu
+}
+
+#[test]
+fn test_trap_at_success_passes() {
+    //LDA #$01; JMP *
+    let mut cpu = generic_cpu(&[0xa9, 0x01, 0x4c, 0x02, 0x04], 0x0400);
+    assert_eq!(
+        run_until_trap(&mut cpu, 0x0402, 1000),
+        TrapResult::Passed(0x0402)
+    );
+    let mut cpu = generic_cpu(&[0xa9, 0x01, 0x4c, 0x02, 0x04], 0x0400);
+    assert_eq!(
+        run_until_trap(&mut cpu, 0x3469, 1000),
+        TrapResult::Failed(0x0402)
+    );
+}
+
+#[test]
+fn test_brk_is_not_a_trap() {
+    //BRK; JMP *, the handler only returns
+    let mut cpu = generic_cpu(&[0x00, 0xff, 0x4c, 0x02, 0x04], 0x0400);
+    cpu.mem_write(0xfffe, 0x00);
+    cpu.mem_write(0xffff, 0x05);
+    cpu.mem_write(0x0500, 0x40);
+    assert_eq!(
+        run_until_trap(&mut cpu, 0x0402, 1000),
+        TrapResult::Passed(0x0402)
+    );
+}
+
+#[test]
+fn test_jam_is_a_trap() {
+    //INX; JAM
+    let mut cpu = generic_cpu(&[0xe8, 0x02], 0x0400);
+    assert_eq!(
+        run_until_trap(&mut cpu, 0x3469, 1000),
+        TrapResult::Failed(0x0401)
+    );
+}
+
+#[test]
+fn test_loops_without_trap_time_out() {
+    //INX; JMP $0400
+    let mut cpu = generic_cpu(&[0xe8, 0x4c, 0x00, 0x04], 0x0400);
+    assert_eq!(run_until_trap(&mut cpu, 0x0400, 1000), TrapResult::TimedOut);
+    assert!(cpu.cycles >= 1000);
+}
+
+#[test]
+#[ignore = "needs 6502_functional_test.bin, see test_roms_dir"]
+fn test_functional_test() {
+    let image = read_test_file("6502_functional_test.bin");
+    let mut cpu = generic_cpu(&image, 0x0000);
+    cpu.program_counter = FUNCTIONAL_TEST_START;
+    assert_eq!(
+        run_until_trap(&mut cpu, FUNCTIONAL_TEST_SUCCESS, 200_000_000),
+        TrapResult::Passed(FUNCTIONAL_TEST_SUCCESS)
+    );
+}
+
+#[test]
+#[ignore = "needs 6502_decimal_test.bin, see test_roms_dir"]
+fn test_decimal_test() {
+    let image = read_test_file("6502_decimal_test.bin");
+    let mut cpu = generic_cpu(&image, DECIMAL_TEST_START);
+    // it ends in a trap of its own, the error flag has the result
+    let result = run_until_trap(&mut cpu, 0, 200_000_000);
+    assert_ne!(result, TrapResult::TimedOut);
+    assert_eq!(cpu.peek(DECIMAL_TEST_ERROR), 0);
+}
@@ -0,0 +1,117 @@
+use std::io::{self, Write};
+
+use crate::apu::Region;
+use crate::cpu::opcode_implementation::CPU;
+use crate::render::frame::Frame;
+use crate::render::frame::{HEIGHT, WIDTH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoFormat {
+    // YUV4MPEG2 with 4:2:0 chroma, readable by ffmpeg, mpv and friends
+    Y4m,
+    // bare RGB24 frames; the reader has to be told the size and rate
+    RawRgb24,
+}
+
+pub struct VideoWriter<W: Write> {
+    writer: W,
+    format: VideoFormat,
+    frame_rate: (u32, u32),
+    header_written: bool,
+    // reused plane buffers for Y4M
+    luma: Vec<u8>,
+    chroma: Vec<u8>,
+}
+
+impl<W: Write> VideoWriter<W> {
+    pub fn new(writer: W, format: VideoFormat, region: Region) -> Self {
+        Self {
+            writer,
+            format,
+            frame_rate: region.frame_rate(),
+            header_written: false,
+            luma: Vec::new(),
+            chroma: Vec::new(),
+        }
+    }
+
+    pub fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+        match self.format {
+            VideoFormat::RawRgb24 => self.writer.write_all(&frame.pixels),
+            VideoFormat::Y4m => {
+                if !self.header_written {
+                    writeln!(
+                        self.writer,
+                        "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C420jpeg",
+                        WIDTH, HEIGHT, self.frame_rate.0, self.frame_rate.1
+                    )?;
+                    self.header_written = true;
+                }
+                self.convert(frame);
+                self.writer.write_all(b"FRAME\n")?;
+                self.writer.write_all(&self.luma)?;
+                self.writer.write_all(&self.chroma)
+            }
+        }
+    }
+
+    // BT.601 studio range. Chroma is averaged over 2x2 blocks, Cb plane then
+    // Cr plane.
+    fn convert(&mut self, frame: &Frame) {
+        self.luma.clear();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let (r, g, b) = frame.pixel(x, y);
+                let (r, g, b) = (r as f32, g as f32, b as f32);
+                let luma = 16.0 + (65.481 * r + 128.553 * g + 24.966 * b) / 255.0;
+                self.luma.push(luma.round() as u8);
+            }
+        }
+
+        let (chroma_width, chroma_height) = (WIDTH / 2, HEIGHT / 2);
+        self.chroma.clear();
+        self.chroma.resize(chroma_width * chroma_height * 2, 0);
+        let (cb_plane, cr_plane) = self.chroma.split_at_mut(chroma_width * chroma_height);
+        for cy in 0..chroma_height {
+            for cx in 0..chroma_width {
+                let (mut r, mut g, mut b) = (0.0, 0.0, 0.0);
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let pixel = frame.pixel(cx * 2 + dx, cy * 2 + dy);
+                    r += pixel.0 as f32 / 4.0;
+                    g += pixel.1 as f32 / 4.0;
+                    b += pixel.2 as f32 / 4.0;
+                }
+                let cb = 128.0 + (-37.797 * r - 74.203 * g + 112.0 * b) / 255.0;
+                let cr = 128.0 + (112.0 * r - 93.786 * g - 18.214 * b) / 255.0;
+                cb_plane[cy * chroma_width + cx] = cb.round() as u8;
+                cr_plane[cy * chroma_width + cx] = cr.round() as u8;
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+// Runs the CPU for `frames` video frames, writing each finished frame.
+pub fn dump_video<W: Write>(
+    cpu: &mut CPU,
+    frames: usize,
+    writer: W,
+    format: VideoFormat,
+) -> io::Result<W> {
+    let mut video = VideoWriter::new(writer, format, cpu.apu.region());
+    let frame_cycles = cpu.apu.region().cpu_cycles_per_frame();
+    let start = cpu.cycles;
+    for frame in 1..=frames {
+        cpu.run_until(start + (frame as f64 * frame_cycles) as usize);
+        video.write_frame(&cpu.frame)?;
+    }
+    video.finish()
+}
+
+#[cfg(test)]
+#[path = "./video_dump_test.rs"]
+mod video_dump_tests;
@@ -0,0 +1,74 @@
+use super::*;
+
+const HEADER: &[u8] = b"YUV4MPEG2 W256 H240 F39375000:655171 Ip A1:1 C420jpeg\n";
+const FRAME_SIZE: usize = WIDTH * HEIGHT * 3 / 2;
+
+fn idle_cpu() -> CPU {
+    let mut cpu = CPU::new();
+    cpu.load_program(vec![0x00]);
+    cpu.reset();
+    cpu
+}
+
+#[test]
+fn test_ntsc_frame_rate() {
+    let (num, den) = Region::Ntsc.frame_rate();
+    assert!((num as f64 / den as f64 - 60.0988).abs() < 0.0001);
+    let (num, den) = Region::Pal.frame_rate();
+    assert!((num as f64 / den as f64 - 50.0070).abs() < 0.0001);
+}
+
+#[test]
+fn test_y4m_stream_layout() {
+    let out = dump_video(&mut idle_cpu(), 3, Vec::new(), VideoFormat::Y4m).unwrap();
+    assert!(out.starts_with(HEADER));
+    let body = &out[HEADER.len()..];
+    assert_eq!(body.len(), 3 * (6 + FRAME_SIZE));
+    for frame in body.chunks(6 + FRAME_SIZE) {
+        assert_eq!(&frame[..6], b"FRAME\n");
+        //no PPU draws yet, so every frame is black: Y 16, Cb/Cr 128
+        assert!(frame[6..6 + WIDTH * HEIGHT].iter().all(|&y| y == 16));
+        assert!(frame[6 + WIDTH * HEIGHT..].iter().all(|&c| c == 128));
+    }
+}
+
+#[test]
+fn test_y4m_colour_conversion() {
+    let mut frame = Frame::new();
+    for y in 0..2 {
+        for x in 0..2 {
+            frame.set_pixel(x, y, (255, 0, 0));
+        }
+    }
+    frame.set_pixel(2, 0, (255, 255, 255));
+    let mut video = VideoWriter::new(Vec::new(), VideoFormat::Y4m, Region::Ntsc);
+    video.write_frame(&frame).unwrap();
+    let out = video.finish().unwrap();
+    let planes = &out[HEADER.len() + 6..];
+    //red: Y 81, Cb 90, Cr 240 / white: Y 235
+    assert_eq!(planes[0], 81);
+    assert_eq!(planes[2], 235);
+    let cb = &planes[WIDTH * HEIGHT..];
+    let cr = &cb[WIDTH * HEIGHT / 4..];
+    assert_eq!((cb[0], cr[0]), (90, 240));
+}
+
+#[test]
+fn test_raw_rgb_frames() {
+    let mut frame = Frame::new();
+    frame.set_pixel(0, 0, (1, 2, 3));
+    let mut video = VideoWriter::new(Vec::new(), VideoFormat::RawRgb24, Region::Ntsc);
+    video.write_frame(&frame).unwrap();
+    video.write_frame(&Frame::new()).unwrap();
+    let out = video.finish().unwrap();
+    assert_eq!(out.len(), 2 * WIDTH * HEIGHT * 3);
+    assert_eq!(&out[0..3], &[1, 2, 3]);
+    assert!(out[3..].iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn test_raw_rgb_dump() {
+    //there is no PPU yet, so the CPU's frames are all blank
+    let out = dump_video(&mut idle_cpu(), 2, Vec::new(), VideoFormat::RawRgb24).unwrap();
+    assert_eq!(out, vec![0; 2 * WIDTH * HEIGHT * 3]);
+}
@@ -0,0 +1,53 @@
+//---S B--- serial potentiometer data, fire button
+const SERIAL: u8 = 0b0001_0000;
+const BUTTON: u8 = 0b0000_1000;
+
+// NES Arkanoid "Vaus" paddle. A strobe latches the 8-bit potentiometer
+// reading, which is then shifted out inverted and MSB first on D4.
+#[derive(Default)]
+pub struct Vaus {
+    position: u8,
+    button: bool,
+    strobe: bool,
+    shift_register: u8,
+}
+
+impl Vaus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Host side: the knob position. Arkanoid expects roughly $62-$F2.
+    pub fn set_position(&mut self, position: u8) {
+        self.position = position;
+    }
+
+    pub fn position(&self) -> u8 {
+        self.position
+    }
+
+    pub fn set_button(&mut self, pressed: bool) {
+        self.button = pressed;
+    }
+
+    pub fn strobe(&mut self, value: u8) {
+        self.strobe = value & 1 == 1;
+        if self.strobe {
+            self.shift_register = !self.position;
+        }
+    }
+
+    pub fn read(&mut self) -> u8 {
+        let mut bits = 0;
+        if self.button {
+            bits |= BUTTON;
+        }
+        if self.shift_register & 0b1000_0000 != 0 {
+            bits |= SERIAL;
+        }
+        if !self.strobe {
+            self.shift_register <<= 1;
+        }
+        bits
+    }
+}
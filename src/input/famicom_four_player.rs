@@ -0,0 +1,26 @@
+use super::controller::Controller;
+
+// Famicom four player adapter using the simple protocol: the two extra
+// controllers sit on the expansion port and are read on D1, player 3 through
+// $4016 and player 4 through $4017.
+#[derive(Default)]
+pub struct FamicomFourPlayer {
+    pub controllers: [Controller; 2],
+}
+
+impl FamicomFourPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn strobe(&mut self, value: u8) {
+        for controller in self.controllers.iter_mut() {
+            controller.strobe(value);
+        }
+    }
+
+    // port is 0 for $4016 and 1 for $4017
+    pub fn read(&mut self, port: usize) -> u8 {
+        self.controllers[port].read() << 1
+    }
+}
@@ -0,0 +1,95 @@
+const ROWS: usize = 9;
+
+// Keys by matrix position: row * 8 + column * 4 + data line (D1-D4).
+#[rustfmt::skip]
+pub const KEYS: [&str; ROWS * 8] = [
+    "]", "[", "RETURN", "F8", "STOP", "YEN", "RSHIFT", "KANA",
+    ";", ":", "@", "F7", "^", "-", "/", "_",
+    "K", "L", "O", "F6", "0", "P", ",", ".",
+    "J", "U", "I", "F5", "8", "9", "N", "M",
+    "H", "G", "Y", "F4", "6", "7", "V", "B",
+    "D", "R", "T", "F3", "4", "5", "C", "F",
+    "A", "S", "W", "F2", "3", "E", "Z", "X",
+    "CTR", "Q", "ESC", "F1", "2", "1", "GRPH", "LSHIFT",
+    "LEFT", "RIGHT", "UP", "CLR", "INS", "DEL", "SPACE", "DOWN",
+];
+
+//---- -KCR keyboard enable, column select, reset to row 0
+const RESET: u8 = 0b0000_0001;
+const COLUMN: u8 = 0b0000_0010;
+const ENABLE: u8 = 0b0000_0100;
+
+// Family BASIC keyboard on the Famicom expansion port. Software scans it by
+// writing $4016: bit 0 resets the scan to row 0, toggling bit 1 selects the
+// column and each 1 -> 0 transition of it moves to the next row. The four keys
+// of the selected half row are read on D1-D4 of $4017, 0 meaning pressed.
+#[derive(Default)]
+pub struct FamilyKeyboard {
+    // true for each held key, indexed like KEYS
+    keys: Vec<bool>,
+    row: usize,
+    column: usize,
+    enabled: bool,
+}
+
+impl FamilyKeyboard {
+    pub fn new() -> Self {
+        Self {
+            keys: vec![false; KEYS.len()],
+            ..Self::default()
+        }
+    }
+
+    pub fn key_index(name: &str) -> Option<usize> {
+        KEYS.iter().position(|key| key.eq_ignore_ascii_case(name))
+    }
+
+    // Host side: returns false when there is no key with that name.
+    pub fn set_key(&mut self, name: &str, pressed: bool) -> bool {
+        match Self::key_index(name) {
+            Some(index) => {
+                self.keys[index] = pressed;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_pressed(&self, name: &str) -> bool {
+        Self::key_index(name).is_some_and(|index| self.keys[index])
+    }
+
+    pub fn release_all(&mut self) {
+        self.keys.iter_mut().for_each(|key| *key = false);
+    }
+
+    pub fn write(&mut self, value: u8) {
+        let column = ((value & COLUMN) >> 1) as usize;
+        self.enabled = value & ENABLE != 0;
+        if value & RESET != 0 {
+            self.row = 0;
+        } else if self.column == 1 && column == 0 {
+            self.row += 1;
+        }
+        self.column = column;
+    }
+
+    // port is 0 for $4016 and 1 for $4017; only $4017 carries key data
+    pub fn read(&self, port: usize) -> u8 {
+        if port == 0 || !self.enabled {
+            return 0;
+        }
+        // past the last row nothing is pressed
+        if self.row >= ROWS {
+            return 0b0001_1110;
+        }
+        let base = self.row * 8 + self.column * 4;
+        let mut bits = 0;
+        for line in 0..4 {
+            if !self.keys[base + line] {
+                bits |= 1 << (line + 1);
+            }
+        }
+        bits
+    }
+}
@@ -0,0 +1,50 @@
+use super::controller::Controller;
+
+// Half of an NES Four Score as seen from one port. The port 1 half carries
+// players 1 and 3, the port 2 half players 2 and 4. After both controllers
+// have been shifted out each half identifies itself with a signature byte.
+pub struct FourScore {
+    pub controllers: [Controller; 2],
+    signature: u8,
+    strobe: bool,
+    reads: u8,
+}
+
+pub const PORT1_SIGNATURE: u8 = 0x10;
+pub const PORT2_SIGNATURE: u8 = 0x20;
+
+impl FourScore {
+    pub fn new(signature: u8) -> Self {
+        Self {
+            controllers: [Controller::new(), Controller::new()],
+            signature,
+            strobe: false,
+            reads: 0,
+        }
+    }
+
+    pub fn strobe(&mut self, value: u8) {
+        self.strobe = value & 1 == 1;
+        for controller in self.controllers.iter_mut() {
+            controller.strobe(value);
+        }
+        if self.strobe {
+            self.reads = 0;
+        }
+    }
+
+    pub fn read(&mut self) -> u8 {
+        if self.strobe {
+            return self.controllers[0].read();
+        }
+        let bit = match self.reads {
+            0..=7 => self.controllers[0].read(),
+            8..=15 => self.controllers[1].read(),
+            // the signature is shifted out most significant bit first
+            16..=23 => (self.signature >> (23 - self.reads)) & 1,
+            _ => 1,
+        };
+        self.reads = self.reads.saturating_add(1);
+        bit
+    }
+}
@@ -0,0 +1,293 @@
+use super::controller::*;
+use super::*;
+use crate::render::frame::Frame;
+
+fn read_bits(ports: &mut InputPorts, addr: u16, count: usize) -> Vec<u8> {
+    (0..count).map(|_| ports.read(addr) & 1).collect()
+}
+
+#[test]
+fn test_controller_shifts_out_buttons_in_order() {
+    let mut ports = InputPorts::new();
+    ports
+        .controller(1)
+        .unwrap()
+        .set_buttons(BUTTON_A | BUTTON_START | BUTTON_RIGHT);
+    ports.write(1);
+    ports.write(0);
+    assert_eq!(
+        read_bits(&mut ports, 0x4016, 8),
+        vec![1, 0, 0, 1, 0, 0, 0, 1]
+    );
+}
+
+#[test]
+fn test_controller_returns_ones_after_eighth_read() {
+    let mut ports = InputPorts::new();
+    ports.write(1);
+    ports.write(0);
+    assert_eq!(read_bits(&mut ports, 0x4016, 8), vec![0; 8]);
+    assert_eq!(read_bits(&mut ports, 0x4016, 4), vec![1; 4]);
+    //restrobing reloads the register
+    ports.write(1);
+    ports.write(0);
+    assert_eq!(read_bits(&mut ports, 0x4016, 1), vec![0]);
+}
+
+#[test]
+fn test_controller_strobe_held_returns_a() {
+    let mut ports = InputPorts::new();
+    ports
+        .controller(1)
+        .unwrap()
+        .set_buttons(BUTTON_A | BUTTON_B);
+    ports.write(1);
+    assert_eq!(read_bits(&mut ports, 0x4016, 4), vec![1; 4]);
+    //the latch follows the buttons while the strobe is high
+    ports.controller(1).unwrap().set_button(BUTTON_A, false);
+    assert_eq!(read_bits(&mut ports, 0x4016, 1), vec![0]);
+    ports.write(0);
+    assert_eq!(read_bits(&mut ports, 0x4016, 2), vec![0, 1]);
+}
+
+#[test]
+fn test_ports_are_independent() {
+    let mut ports = InputPorts::new();
+    ports.controller(1).unwrap().set_buttons(BUTTON_UP);
+    ports.controller(2).unwrap().set_buttons(BUTTON_DOWN);
+    ports.write(1);
+    ports.write(0);
+    assert_eq!(
+        read_bits(&mut ports, 0x4016, 8),
+        vec![0, 0, 0, 0, 1, 0, 0, 0]
+    );
+    assert_eq!(
+        read_bits(&mut ports, 0x4017, 8),
+        vec![0, 0, 0, 0, 0, 1, 0, 0]
+    );
+    assert!(ports.controller(3).is_none());
+}
+
+#[test]
+fn test_open_bus_upper_bits() {
+    let mut ports = InputPorts::new();
+    ports.controller(1).unwrap().set_buttons(BUTTON_A);
+    ports.write(1);
+    ports.write(0);
+    assert_eq!(ports.read(0x4016), 0x41);
+    assert_eq!(ports.read(0x4016), 0x40);
+    ports.port2 = InputDevice::Disconnected;
+    assert_eq!(ports.read(0x4017), 0x40);
+}
+
+#[test]
+fn test_four_score_reads_all_players_then_signature() {
+    let mut ports = InputPorts::with_device(ExpansionDevice::FourScore);
+    ports.controller(1).unwrap().set_buttons(BUTTON_A);
+    ports.controller(3).unwrap().set_buttons(BUTTON_RIGHT);
+    ports.controller(2).unwrap().set_buttons(BUTTON_B);
+    ports.controller(4).unwrap().set_buttons(BUTTON_UP);
+    ports.write(1);
+    ports.write(0);
+    assert_eq!(
+        read_bits(&mut ports, 0x4016, 16),
+        vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]
+    );
+    //$10 on port 1, $20 on port 2, most significant bit first
+    assert_eq!(
+        read_bits(&mut ports, 0x4016, 8),
+        vec![0, 0, 0, 1, 0, 0, 0, 0]
+    );
+    assert_eq!(read_bits(&mut ports, 0x4016, 2), vec![1, 1]);
+    assert_eq!(
+        read_bits(&mut ports, 0x4017, 16),
+        vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]
+    );
+    assert_eq!(
+        read_bits(&mut ports, 0x4017, 8),
+        vec![0, 0, 1, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn test_four_score_strobe_restarts_sequence() {
+    let mut ports = InputPorts::with_device(ExpansionDevice::FourScore);
+    ports.controller(1).unwrap().set_buttons(BUTTON_A);
+    ports.write(1);
+    ports.write(0);
+    read_bits(&mut ports, 0x4016, 20);
+    ports.write(1);
+    ports.write(0);
+    assert_eq!(read_bits(&mut ports, 0x4016, 1), vec![1]);
+}
+
+#[test]
+fn test_famicom_four_player_uses_d1() {
+    let mut ports = InputPorts::with_device(ExpansionDevice::FamicomFourPlayer);
+    ports.controller(1).unwrap().set_buttons(BUTTON_A);
+    ports
+        .controller(3)
+        .unwrap()
+        .set_buttons(BUTTON_A | BUTTON_B);
+    ports.controller(4).unwrap().set_buttons(BUTTON_SELECT);
+    ports.write(1);
+    ports.write(0);
+    assert_eq!(ports.read(0x4016), 0x43);
+    assert_eq!(ports.read(0x4016), 0x42);
+    assert_eq!(ports.read(0x4016), 0x40);
+    assert_eq!(ports.read(0x4017), 0x40);
+    assert_eq!(ports.read(0x4017), 0x40);
+    assert_eq!(ports.read(0x4017), 0x42);
+}
+
+#[test]
+fn test_device_from_nes2_header() {
+    let mut raw = vec![
+        b'N', b'E', b'S', 0x1A, 1, 1, 0, 0x08, 0, 0, 0, 0, 0, 0, 0, 0x02,
+    ];
+    let header = Header::parse(&raw).unwrap();
+    let mut ports = InputPorts::for_header(&header);
+    assert!(matches!(ports.port1, InputDevice::FourScore(_)));
+    assert!(ports.controller(4).is_some());
+
+    raw[15] = 0x03;
+    let ports = InputPorts::for_header(&Header::parse(&raw).unwrap());
+    assert!(matches!(
+        ports.expansion,
+        ExpansionPortDevice::FamicomFourPlayer(_)
+    ));
+
+    //unknown ids and iNES 1.0 files fall back to two controllers
+    raw[15] = 0x3F;
+    let mut ports = InputPorts::for_header(&Header::parse(&raw).unwrap());
+    assert!(ports.controller(2).is_some());
+    assert!(ports.controller(3).is_none());
+    raw[7] = 0;
+    raw[15] = 0x02;
+    let ports = InputPorts::for_header(&Header::parse(&raw).unwrap());
+    assert!(matches!(ports.port1, InputDevice::Controller(_)));
+}
+
+fn white_square(x: usize, y: usize, size: usize) -> Frame {
+    let mut frame = Frame::new();
+    for row in y..y + size {
+        for column in x..x + size {
+            frame.set_pixel(column, row, (0xFF, 0xFF, 0xFF));
+        }
+    }
+    frame
+}
+
+#[test]
+fn test_zapper_trigger_and_dark_screen() {
+    let mut ports = InputPorts::with_device(ExpansionDevice::Zapper);
+    let zapper = ports.zapper(2).unwrap();
+    zapper.set_position(Some((100, 100)));
+    zapper.sense(&Frame::new(), 120, 0);
+    assert_eq!(ports.read(0x4017), 0x48);
+    ports.zapper(2).unwrap().set_trigger(true);
+    assert_eq!(ports.read(0x4017), 0x58);
+    assert!(ports.zapper(1).is_none());
+    assert!(ports.controller(1).is_some());
+}
+
+#[test]
+fn test_zapper_senses_light_after_beam_passes() {
+    let mut ports = InputPorts::with_device(ExpansionDevice::Zapper);
+    let frame = white_square(96, 96, 8);
+    ports.zapper(2).unwrap().set_position(Some((100, 100)));
+
+    //the beam hasn't drawn the target yet
+    ports.update_video(&frame, 90, 200);
+    assert_eq!(ports.read(0x4017) & 0b0000_1000, 0b0000_1000);
+    //just drawn
+    ports.update_video(&frame, 100, 120);
+    assert_eq!(ports.read(0x4017) & 0b0000_1000, 0);
+    //still lit a few scanlines later
+    ports.update_video(&frame, 115, 0);
+    assert!(ports.zapper(2).unwrap().light_detected());
+    //the sensor has gone dark again
+    ports.update_video(&frame, 140, 0);
+    assert!(!ports.zapper(2).unwrap().light_detected());
+}
+
+#[test]
+fn test_zapper_misses_away_from_target() {
+    let mut ports = InputPorts::with_device(ExpansionDevice::Zapper);
+    let frame = white_square(96, 96, 8);
+    ports.zapper(2).unwrap().set_position(Some((20, 100)));
+    ports.update_video(&frame, 110, 0);
+    assert!(!ports.zapper(2).unwrap().light_detected());
+    //pointing off screen never sees light
+    ports.zapper(2).unwrap().set_position(None);
+    ports.update_video(&frame, 110, 0);
+    assert!(!ports.zapper(2).unwrap().light_detected());
+    ports.zapper(2).unwrap().set_position(Some((300, 10)));
+    assert_eq!(ports.zapper(2).unwrap().position(), None);
+}
+
+#[test]
+fn test_vaus_shifts_out_inverted_position() {
+    let mut ports = InputPorts::with_device(ExpansionDevice::ArkanoidVaus);
+    ports.vaus(2).unwrap().set_position(0b1010_0011);
+    ports.vaus(2).unwrap().set_button(true);
+    ports.write(1);
+    ports.write(0);
+    let bits: Vec<u8> = (0..8)
+        .map(|_| (ports.read(0x4017) & 0b0001_0000) >> 4)
+        .collect();
+    assert_eq!(bits, vec![0, 1, 0, 1, 1, 1, 0, 0]);
+    assert_eq!(ports.read(0x4017) & 0b0000_1000, 0b0000_1000);
+    ports.vaus(2).unwrap().set_button(false);
+    assert_eq!(ports.read(0x4017) & 0b0000_1000, 0);
+}
+
+#[test]
+fn test_power_pad_streams() {
+    let mut ports = InputPorts::new();
+    ports.port2 = InputDevice::PowerPad(power_pad::PowerPad::new());
+    let pad = ports.power_pad(2).unwrap();
+    assert!(pad.set_button(1, true));
+    assert!(pad.set_button(9, true));
+    assert!(pad.set_button(12, true));
+    assert!(!pad.set_button(0, true));
+    assert!(!pad.set_button(13, true));
+    ports.write(1);
+    ports.write(0);
+    let (low, high): (Vec<u8>, Vec<u8>) = (0..10)
+        .map(|_| {
+            let value = ports.read(0x4017);
+            ((value >> 3) & 1, (value >> 4) & 1)
+        })
+        .unzip();
+    //low: 2, 1, 5, 9, 6, 10, 11, 7 / high: 4, 3, 12, 8 then 1s
+    assert_eq!(low, vec![0, 1, 0, 1, 0, 0, 0, 0, 1, 1]);
+    assert_eq!(high, vec![0, 0, 1, 0, 1, 1, 1, 1, 1, 1]);
+    assert!(ports.power_pad(1).is_none());
+}
+
+#[test]
+fn test_family_keyboard_scan() {
+    let mut ports = InputPorts::with_device(ExpansionDevice::FamilyKeyboard);
+    let keyboard = ports.family_keyboard().unwrap();
+    assert!(keyboard.set_key("return", true));
+    assert!(keyboard.set_key("SPACE", true));
+    assert!(!keyboard.set_key("no such key", true));
+
+    //reset and enable, row 0 column 0: ] [ RETURN F8
+    ports.write(0b101);
+    assert_eq!(ports.read(0x4017), 0x40 | 0b0001_0110);
+    //column 1 of row 0 has nothing held
+    ports.write(0b110);
+    assert_eq!(ports.read(0x4017), 0x40 | 0b0001_1110);
+    //walk down to row 8 column 1: INS DEL SPACE DOWN
+    for _ in 0..8 {
+        ports.write(0b100);
+        ports.write(0b110);
+    }
+    assert_eq!(ports.read(0x4017), 0x40 | 0b0001_0110);
+    //$4016 and a disabled keyboard carry no key data
+    assert_eq!(ports.read(0x4016) & 0b0001_1110, 0);
+    ports.write(0b000);
+    assert_eq!(ports.read(0x4017) & 0b0001_1110, 0);
+}
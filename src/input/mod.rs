@@ -0,0 +1,274 @@
+pub mod arkanoid;
+pub mod controller;
+pub mod famicom_four_player;
+pub mod family_keyboard;
+pub mod four_score;
+pub mod power_pad;
+pub mod zapper;
+
+use crate::cartridge::header::Header;
+use crate::render::frame::Frame;
+use arkanoid::Vaus;
+use controller::Controller;
+use famicom_four_player::FamicomFourPlayer;
+use family_keyboard::FamilyKeyboard;
+use four_score::FourScore;
+use power_pad::PowerPad;
+use zapper::Zapper;
+
+// Bits 5-7 of $4016/$4017 aren't driven by the ports, so they read back
+// whatever was last on the data bus: the high byte of the address, $40.
+const OPEN_BUS: u8 = 0x40;
+
+// The devices that can be picked for a game, named after the NES 2.0
+// default expansion device ids that select them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExpansionDevice {
+    StandardControllers,
+    FourScore,
+    FamicomFourPlayer,
+    Zapper,
+    PowerPad,
+    ArkanoidVaus,
+    FamilyKeyboard,
+}
+
+impl ExpansionDevice {
+    // NES 2.0 header byte 15; ids for devices we don't emulate give None.
+    pub fn from_nes2_id(id: u8) -> Option<ExpansionDevice> {
+        match id {
+            0x01 => Some(ExpansionDevice::StandardControllers),
+            0x02 => Some(ExpansionDevice::FourScore),
+            0x03 => Some(ExpansionDevice::FamicomFourPlayer),
+            0x08 => Some(ExpansionDevice::Zapper),
+            // side A and side B of the mat only differ in the printed layout
+            0x0B | 0x0C => Some(ExpansionDevice::PowerPad),
+            0x0F => Some(ExpansionDevice::ArkanoidVaus),
+            0x23 => Some(ExpansionDevice::FamilyKeyboard),
+            _ => None,
+        }
+    }
+}
+
+pub enum InputDevice {
+    Disconnected,
+    Controller(Controller),
+    FourScore(FourScore),
+    Zapper(Zapper),
+    PowerPad(PowerPad),
+    Vaus(Vaus),
+}
+
+impl InputDevice {
+    // Every device sees the OUT bits of $4016 writes.
+    fn strobe(&mut self, value: u8) {
+        match self {
+            InputDevice::Disconnected => {}
+            InputDevice::Controller(controller) => controller.strobe(value),
+            InputDevice::FourScore(four_score) => four_score.strobe(value),
+            InputDevice::Zapper(_) => {}
+            InputDevice::PowerPad(power_pad) => power_pad.strobe(value),
+            InputDevice::Vaus(vaus) => vaus.strobe(value),
+        }
+    }
+
+    // Returns the device's D0-D4 lines.
+    fn read(&mut self) -> u8 {
+        match self {
+            InputDevice::Disconnected => 0,
+            InputDevice::Controller(controller) => controller.read(),
+            InputDevice::FourScore(four_score) => four_score.read(),
+            InputDevice::Zapper(zapper) => zapper.read(),
+            InputDevice::PowerPad(power_pad) => power_pad.read(),
+            InputDevice::Vaus(vaus) => vaus.read(),
+        }
+    }
+}
+
+// Famicom expansion port. Unlike the front ports it is wired to both $4016
+// and $4017, and its devices usually answer on D1-D4.
+pub enum ExpansionPortDevice {
+    Disconnected,
+    FamicomFourPlayer(FamicomFourPlayer),
+    FamilyKeyboard(FamilyKeyboard),
+}
+
+impl ExpansionPortDevice {
+    fn strobe(&mut self, value: u8) {
+        match self {
+            ExpansionPortDevice::Disconnected => {}
+            ExpansionPortDevice::FamicomFourPlayer(adapter) => adapter.strobe(value),
+            ExpansionPortDevice::FamilyKeyboard(keyboard) => keyboard.write(value),
+        }
+    }
+
+    // port is 0 for $4016 and 1 for $4017
+    fn read(&mut self, port: usize) -> u8 {
+        match self {
+            ExpansionPortDevice::Disconnected => 0,
+            ExpansionPortDevice::FamicomFourPlayer(adapter) => adapter.read(port),
+            ExpansionPortDevice::FamilyKeyboard(keyboard) => keyboard.read(port),
+        }
+    }
+}
+
+pub struct InputPorts {
+    pub port1: InputDevice,
+    pub port2: InputDevice,
+    pub expansion: ExpansionPortDevice,
+}
+
+impl Default for InputPorts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputPorts {
+    pub fn new() -> Self {
+        Self::with_device(ExpansionDevice::StandardControllers)
+    }
+
+    pub fn with_device(device: ExpansionDevice) -> Self {
+        match device {
+            ExpansionDevice::StandardControllers => Self {
+                port1: InputDevice::Controller(Controller::new()),
+                port2: InputDevice::Controller(Controller::new()),
+                expansion: ExpansionPortDevice::Disconnected,
+            },
+            ExpansionDevice::FourScore => Self {
+                port1: InputDevice::FourScore(FourScore::new(four_score::PORT1_SIGNATURE)),
+                port2: InputDevice::FourScore(FourScore::new(four_score::PORT2_SIGNATURE)),
+                expansion: ExpansionPortDevice::Disconnected,
+            },
+            ExpansionDevice::FamicomFourPlayer => Self {
+                port1: InputDevice::Controller(Controller::new()),
+                port2: InputDevice::Controller(Controller::new()),
+                expansion: ExpansionPortDevice::FamicomFourPlayer(FamicomFourPlayer::new()),
+            },
+            // the light gun, mat and paddle go in port 2, leaving player 1 a
+            // controller
+            ExpansionDevice::Zapper => Self::with_port2(InputDevice::Zapper(Zapper::new())),
+            ExpansionDevice::PowerPad => Self::with_port2(InputDevice::PowerPad(PowerPad::new())),
+            ExpansionDevice::ArkanoidVaus => Self::with_port2(InputDevice::Vaus(Vaus::new())),
+            ExpansionDevice::FamilyKeyboard => Self {
+                port1: InputDevice::Controller(Controller::new()),
+                port2: InputDevice::Controller(Controller::new()),
+                expansion: ExpansionPortDevice::FamilyKeyboard(FamilyKeyboard::new()),
+            },
+        }
+    }
+
+    fn with_port2(device: InputDevice) -> Self {
+        Self {
+            port1: InputDevice::Controller(Controller::new()),
+            port2: device,
+            expansion: ExpansionPortDevice::Disconnected,
+        }
+    }
+
+    // Uses the header's default expansion device when NES 2.0 names one we
+    // support, and plain controllers otherwise.
+    pub fn for_header(header: &Header) -> Self {
+        let device = if header.nes2 {
+            ExpansionDevice::from_nes2_id(header.default_expansion_device)
+        } else {
+            None
+        };
+        Self::with_device(device.unwrap_or(ExpansionDevice::StandardControllers))
+    }
+
+    // Port 1 or 2. Hosts can plug a different device in by assigning to it.
+    pub fn port(&mut self, port: usize) -> Option<&mut InputDevice> {
+        match port {
+            1 => Some(&mut self.port1),
+            2 => Some(&mut self.port2),
+            _ => None,
+        }
+    }
+
+    // The zapper plugged into port 1 or 2, if any.
+    pub fn zapper(&mut self, port: usize) -> Option<&mut Zapper> {
+        match self.port(port)? {
+            InputDevice::Zapper(zapper) => Some(zapper),
+            _ => None,
+        }
+    }
+
+    pub fn power_pad(&mut self, port: usize) -> Option<&mut PowerPad> {
+        match self.port(port)? {
+            InputDevice::PowerPad(power_pad) => Some(power_pad),
+            _ => None,
+        }
+    }
+
+    pub fn vaus(&mut self, port: usize) -> Option<&mut Vaus> {
+        match self.port(port)? {
+            InputDevice::Vaus(vaus) => Some(vaus),
+            _ => None,
+        }
+    }
+
+    pub fn family_keyboard(&mut self) -> Option<&mut FamilyKeyboard> {
+        match &mut self.expansion {
+            ExpansionPortDevice::FamilyKeyboard(keyboard) => Some(keyboard),
+            _ => None,
+        }
+    }
+
+    // Lets light sensing devices see the picture as the beam draws it.
+    pub fn update_video(&mut self, frame: &Frame, scanline: usize, dot: usize) {
+        for device in [&mut self.port1, &mut self.port2] {
+            if let InputDevice::Zapper(zapper) = device {
+                zapper.sense(frame, scanline, dot);
+            }
+        }
+    }
+
+    // $4016 writes
+    pub fn write(&mut self, value: u8) {
+        self.port1.strobe(value);
+        self.port2.strobe(value);
+        self.expansion.strobe(value);
+    }
+
+    // $4016 reads port 1, $4017 reads port 2, both see the expansion port
+    pub fn read(&mut self, addr: u16) -> u8 {
+        let (device, port) = match addr {
+            0x4016 => (&mut self.port1, 0),
+            0x4017 => (&mut self.port2, 1),
+            _ => panic!("input: {:04X} is not a controller port", addr),
+        };
+        let bits = device.read() | (self.expansion.read(port) & 0b0001_1110);
+        OPEN_BUS | (bits & 0b0001_1111)
+    }
+
+    // The standard controller for players 1-4, if one is plugged in. Players
+    // 3 and 4 come from a Four Score or a Famicom four player adapter.
+    pub fn controller(&mut self, player: usize) -> Option<&mut Controller> {
+        let (device, slot) = match player {
+            1 => (&mut self.port1, 0),
+            2 => (&mut self.port2, 0),
+            3 => (&mut self.port1, 1),
+            4 => (&mut self.port2, 1),
+            _ => return None,
+        };
+        match (device, slot) {
+            (InputDevice::Controller(controller), 0) => return Some(controller),
+            (InputDevice::FourScore(four_score), _) => {
+                return Some(&mut four_score.controllers[slot])
+            }
+            _ => {}
+        }
+        match &mut self.expansion {
+            ExpansionPortDevice::FamicomFourPlayer(adapter) if slot == 1 => {
+                Some(&mut adapter.controllers[player - 3])
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./input_test.rs"]
+mod input_tests;
@@ -0,0 +1,93 @@
+//---H L--- high and low serial streams
+const HIGH: u8 = 0b0001_0000;
+const LOW: u8 = 0b0000_1000;
+
+// Button numbers in the order they come out of each stream.
+const LOW_ORDER: [u8; 8] = [2, 1, 5, 9, 6, 10, 11, 7];
+const HIGH_ORDER: [u8; 4] = [4, 3, 12, 8];
+
+// Power Pad mat: twelve buttons read through two shift registers, eight on D3
+// and four on D4. Both registers shift in 1s, as on the standard controller.
+#[derive(Default)]
+pub struct PowerPad {
+    // bit n - 1 is button n
+    buttons: u16,
+    strobe: bool,
+    low: u8,
+    high: u8,
+}
+
+impl PowerPad {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Host side: a mask of held buttons, bit 0 being button 1.
+    pub fn set_buttons(&mut self, buttons: u16) {
+        self.buttons = buttons & 0x0FFF;
+        if self.strobe {
+            self.latch();
+        }
+    }
+
+    // button is the number printed on the mat, 1-12. Returns false when
+    // there is no such button.
+    pub fn set_button(&mut self, button: u8, pressed: bool) -> bool {
+        if !(1..=12).contains(&button) {
+            return false;
+        }
+        let mask = 1 << (button - 1);
+        if pressed {
+            self.set_buttons(self.buttons | mask);
+        } else {
+            self.set_buttons(self.buttons & !mask);
+        }
+        true
+    }
+
+    pub fn buttons(&self) -> u16 {
+        self.buttons
+    }
+
+    fn pressed(&self, button: u8) -> bool {
+        self.buttons & (1 << (button - 1)) != 0
+    }
+
+    fn latch(&mut self) {
+        self.low = 0;
+        for (bit, &button) in LOW_ORDER.iter().enumerate() {
+            if self.pressed(button) {
+                self.low |= 1 << bit;
+            }
+        }
+        // the upper half of the high register is always set
+        self.high = 0xF0;
+        for (bit, &button) in HIGH_ORDER.iter().enumerate() {
+            if self.pressed(button) {
+                self.high |= 1 << bit;
+            }
+        }
+    }
+
+    pub fn strobe(&mut self, value: u8) {
+        self.strobe = value & 1 == 1;
+        if self.strobe {
+            self.latch();
+        }
+    }
+
+    pub fn read(&mut self) -> u8 {
+        let mut bits = 0;
+        if self.low & 1 != 0 {
+            bits |= LOW;
+        }
+        if self.high & 1 != 0 {
+            bits |= HIGH;
+        }
+        if !self.strobe {
+            self.low = (self.low >> 1) | 0b1000_0000;
+            self.high = (self.high >> 1) | 0b1000_0000;
+        }
+        bits
+    }
+}
@@ -0,0 +1,91 @@
+use crate::render::frame::Frame;
+use crate::render::frame::{HEIGHT, WIDTH};
+
+// The photodiode sees a small circle of the screen, not a single pixel.
+const SENSE_RADIUS: usize = 3;
+// Once lit the sensor output stays active for roughly this many scanlines.
+const LIGHT_SCANLINES: usize = 26;
+// Dimmer than this and the sensor doesn't react.
+const LIGHT_THRESHOLD: u8 = 0x55;
+
+//---T L--- trigger pulled, light not detected
+const TRIGGER: u8 = 0b0001_0000;
+const NO_LIGHT: u8 = 0b0000_1000;
+
+// NES Zapper. The host aims it with a cursor position in framebuffer pixels;
+// the CPU reports the beam position through sense() on every port read so
+// the light bit follows the scanline that is being drawn. Until a PPU draws
+// into the frame the sensor only ever sees a dark screen.
+#[derive(Default)]
+pub struct Zapper {
+    position: Option<(usize, usize)>,
+    trigger: bool,
+    light: bool,
+}
+
+impl Zapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // None points the gun away from the screen.
+    pub fn set_position(&mut self, position: Option<(usize, usize)>) {
+        self.position = position.filter(|&(x, y)| x < WIDTH && y < HEIGHT);
+        if self.position.is_none() {
+            self.light = false;
+        }
+    }
+
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.position
+    }
+
+    pub fn set_trigger(&mut self, pulled: bool) {
+        self.trigger = pulled;
+    }
+
+    // Called with the frame being drawn and the beam's current position. The
+    // sensor is lit while a bright pixel near the aim point was drawn within
+    // the last LIGHT_SCANLINES scanlines.
+    pub fn sense(&mut self, frame: &Frame, scanline: usize, dot: usize) {
+        self.light = false;
+        let (aim_x, aim_y) = match self.position {
+            Some(position) => position,
+            None => return,
+        };
+        let top = aim_y.saturating_sub(SENSE_RADIUS);
+        let bottom = (aim_y + SENSE_RADIUS).min(HEIGHT - 1);
+        let left = aim_x.saturating_sub(SENSE_RADIUS);
+        let right = (aim_x + SENSE_RADIUS).min(WIDTH - 1);
+        for y in top..=bottom {
+            if y > scanline || scanline - y > LIGHT_SCANLINES {
+                continue;
+            }
+            for x in left..=right {
+                // the beam hasn't reached this pixel yet
+                if y == scanline && x > dot {
+                    break;
+                }
+                if frame.brightness(x, y) >= LIGHT_THRESHOLD {
+                    self.light = true;
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn light_detected(&self) -> bool {
+        self.light
+    }
+
+    pub fn read(&self) -> u8 {
+        let mut bits = 0;
+        if self.trigger {
+            bits |= TRIGGER;
+        }
+        if !self.light {
+            bits |= NO_LIGHT;
+        }
+        bits
+    }
+}
@@ -0,0 +1,9 @@
+#![allow(clippy::upper_case_acronyms)]
+pub mod apu;
+pub mod cartridge;
+pub mod cli;
+pub mod cpu;
+pub mod frontend;
+pub mod headless;
+pub mod input;
+pub mod render;
@@ -1,5 +1,7 @@
-mod cpu;
+use std::env;
+use std::process;
 
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().skip(1).collect();
+    process::exit(nesoxide::cli::run(&args));
 }
@@ -0,0 +1,45 @@
+// the PPU's output resolution
+pub const WIDTH: usize = 256;
+pub const HEIGHT: usize = 240;
+
+// A finished picture at the PPU's native resolution, packed as RGB24.
+#[derive(Clone)]
+pub struct Frame {
+    pub pixels: Vec<u8>,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Frame {
+    pub fn new() -> Self {
+        Self {
+            pixels: vec![0; WIDTH * HEIGHT * 3],
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> (u8, u8, u8) {
+        let offset = (y * WIDTH + x) * 3;
+        (
+            self.pixels[offset],
+            self.pixels[offset + 1],
+            self.pixels[offset + 2],
+        )
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, rgb: (u8, u8, u8)) {
+        let offset = (y * WIDTH + x) * 3;
+        self.pixels[offset] = rgb.0;
+        self.pixels[offset + 1] = rgb.1;
+        self.pixels[offset + 2] = rgb.2;
+    }
+
+    // Rec. 601 luma, 0-255
+    pub fn brightness(&self, x: usize, y: usize) -> u8 {
+        let (r, g, b) = self.pixel(x, y);
+        ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+    }
+}
@@ -0,0 +1,4 @@
+pub mod frame;
+pub mod ntsc;
+pub mod png;
+pub mod screenshot;
@@ -0,0 +1,233 @@
+use std::f32::consts::PI;
+
+use super::frame::{HEIGHT, WIDTH};
+
+// The composite signal is generated at twice the master clock: 8 samples per
+// PPU dot and 12 samples per colour subcarrier cycle.
+const SAMPLES_PER_PIXEL: usize = 8;
+const SAMPLES_PER_CYCLE: usize = 12;
+const SAMPLES_PER_LINE: usize = WIDTH * SAMPLES_PER_PIXEL;
+const DOTS_PER_SCANLINE: usize = 341;
+const SCANLINES_PER_FRAME: usize = 262;
+
+// Voltage levels relative to sync, as measured on a 2C02.
+const BLACK: f32 = 0.518;
+const WHITE: f32 = 1.962;
+const ATTENUATION: f32 = 0.746;
+const LEVELS_LOW: [f32; 4] = [0.350, 0.518, 0.962, 1.550];
+const LEVELS_HIGH: [f32; 4] = [1.094, 1.506, 1.962, 1.962];
+
+// Phase offset (in samples) between the colour burst and the decoder's
+// reference so that hue 0.0 matches the standard palette.
+const BURST_OFFSET: f32 = 3.9;
+const DISPLAY_GAMMA: f32 = 2.2;
+const SOURCE_GAMMA: f32 = 1.8;
+
+pub struct NtscSettings {
+    // -1.0 blurs, 0.0 is the plain decoder output, 1.0 sharpens luma edges
+    pub sharpness: f32,
+    // multiplier on the chroma amplitude, 0.0 gives a greyscale picture
+    pub saturation: f32,
+    // hue rotation in degrees
+    pub hue: f32,
+    pub output_width: usize,
+    // shift the subcarrier phase between frames like the real PPU does
+    pub dot_crawl: bool,
+}
+
+impl Default for NtscSettings {
+    fn default() -> Self {
+        Self {
+            sharpness: 0.0,
+            saturation: 1.0,
+            hue: 0.0,
+            output_width: 640,
+            dot_crawl: true,
+        }
+    }
+}
+
+// Optional post-process from 9-bit PPU pixels to RGB. Nothing calls it from
+// the CLI or the frontends yet: there is no PPU to produce those pixels, and
+// `Frame` only holds the finished RGB picture.
+pub struct NtscFilter {
+    settings: NtscSettings,
+    frame_phase: usize,
+    odd_frame: bool,
+    signal: Vec<f32>,
+    luma: Vec<f32>,
+    chroma: Vec<(f32, f32)>,
+    cos_table: [f32; SAMPLES_PER_CYCLE],
+    sin_table: [f32; SAMPLES_PER_CYCLE],
+}
+
+impl NtscFilter {
+    pub fn new(settings: NtscSettings) -> Self {
+        let mut filter = Self {
+            settings,
+            frame_phase: 0,
+            odd_frame: false,
+            signal: vec![0.0; SAMPLES_PER_LINE],
+            luma: Vec::new(),
+            chroma: Vec::new(),
+            cos_table: [0.0; SAMPLES_PER_CYCLE],
+            sin_table: [0.0; SAMPLES_PER_CYCLE],
+        };
+        filter.build_tables();
+        filter
+    }
+
+    pub fn settings(&self) -> &NtscSettings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: NtscSettings) {
+        self.settings = settings;
+        self.build_tables();
+    }
+
+    pub fn output_width(&self) -> usize {
+        self.settings.output_width
+    }
+
+    pub fn output_height(&self) -> usize {
+        HEIGHT
+    }
+
+    // Takes one frame of 9-bit PPU output (palette index in bits 0-5, the
+    // PPUMASK emphasis bits in bits 6-8) and returns it as packed RGB24.
+    pub fn filter_frame(&mut self, pixels: &[u16]) -> Vec<u8> {
+        assert_eq!(pixels.len(), WIDTH * HEIGHT, "ntsc: frame size mismatch");
+        let width = self.settings.output_width;
+        let mut out = vec![0u8; width * HEIGHT * 3];
+        for y in 0..HEIGHT {
+            let phase =
+                (self.frame_phase + y * DOTS_PER_SCANLINE * SAMPLES_PER_PIXEL) % SAMPLES_PER_CYCLE;
+            self.encode_scanline(&pixels[y * WIDTH..(y + 1) * WIDTH], phase);
+            self.decode_scanline(phase, &mut out[y * width * 3..(y + 1) * width * 3]);
+        }
+        self.advance_frame();
+        out
+    }
+
+    fn build_tables(&mut self) {
+        let hue = self.settings.hue / 360.0 * SAMPLES_PER_CYCLE as f32;
+        for p in 0..SAMPLES_PER_CYCLE {
+            let angle = PI * (p as f32 + BURST_OFFSET + hue) / 6.0;
+            self.cos_table[p] = angle.cos();
+            self.sin_table[p] = angle.sin();
+        }
+    }
+
+    fn advance_frame(&mut self) {
+        if !self.settings.dot_crawl {
+            return;
+        }
+        // Odd frames are one dot shorter, which is what makes the crawl
+        // pattern alternate instead of scrolling in one direction.
+        let mut dots = DOTS_PER_SCANLINE * SCANLINES_PER_FRAME;
+        if self.odd_frame {
+            dots -= 1;
+        }
+        self.frame_phase = (self.frame_phase + dots * SAMPLES_PER_PIXEL) % SAMPLES_PER_CYCLE;
+        self.odd_frame = !self.odd_frame;
+    }
+
+    fn encode_scanline(&mut self, line: &[u16], phase: usize) {
+        for (x, &pixel) in line.iter().enumerate() {
+            for p in 0..SAMPLES_PER_PIXEL {
+                let level = composite_level(pixel, phase + x * SAMPLES_PER_PIXEL + p);
+                self.signal[x * SAMPLES_PER_PIXEL + p] = (level - BLACK) / (WHITE - BLACK);
+            }
+        }
+    }
+
+    fn decode_scanline(&mut self, phase: usize, out: &mut [u8]) {
+        let width = self.settings.output_width;
+        self.luma.clear();
+        self.chroma.clear();
+        for x in 0..width {
+            let center = x * SAMPLES_PER_LINE / width;
+            let begin = center.saturating_sub(SAMPLES_PER_CYCLE / 2);
+            let end = (center + SAMPLES_PER_CYCLE / 2).min(SAMPLES_PER_LINE);
+            let (mut y, mut i, mut q) = (0.0, 0.0, 0.0);
+            for p in begin..end {
+                let level = self.signal[p] / SAMPLES_PER_CYCLE as f32;
+                let index = (phase + p) % SAMPLES_PER_CYCLE;
+                y += level;
+                i += level * self.cos_table[index];
+                q += level * self.sin_table[index];
+            }
+            self.luma.push(y);
+            self.chroma.push((i, q));
+        }
+
+        let sharpness = self.settings.sharpness;
+        let saturation = self.settings.saturation;
+        for x in 0..width {
+            let left = self.luma[x.saturating_sub(1)];
+            let right = self.luma[(x + 1).min(width - 1)];
+            let y = self.luma[x] + sharpness * (self.luma[x] - (left + right) / 2.0);
+            let (i, q) = self.chroma[x];
+            let (i, q) = (i * saturation, q * saturation);
+            out[x * 3] = to_channel(y + 0.946882 * i + 0.623557 * q);
+            out[x * 3 + 1] = to_channel(y - 0.274788 * i - 0.635691 * q);
+            out[x * 3 + 2] = to_channel(y - 1.108545 * i + 1.709007 * q);
+        }
+    }
+}
+
+impl Default for NtscFilter {
+    fn default() -> Self {
+        Self::new(NtscSettings::default())
+    }
+}
+
+fn in_color_phase(color: u16, phase: usize) -> bool {
+    (color as usize + phase) % SAMPLES_PER_CYCLE < SAMPLES_PER_CYCLE / 2
+}
+
+fn composite_level(pixel: u16, phase: usize) -> f32 {
+    let color = pixel & 0x0F;
+    let mut level = ((pixel >> 4) & 0x03) as usize;
+    let emphasis = (pixel >> 6) & 0x07;
+    // colours $xE and $xF are always emitted at the black level
+    if color > 13 {
+        level = 1;
+    }
+
+    let mut low = LEVELS_LOW[level];
+    let mut high = LEVELS_HIGH[level];
+    if color == 0 {
+        low = high;
+    }
+    if color > 12 {
+        high = low;
+    }
+
+    let mut signal = if in_color_phase(color, phase) {
+        high
+    } else {
+        low
+    };
+    if (emphasis & 0b001 != 0 && in_color_phase(0, phase))
+        || (emphasis & 0b010 != 0 && in_color_phase(4, phase))
+        || (emphasis & 0b100 != 0 && in_color_phase(8, phase))
+    {
+        signal *= ATTENUATION;
+    }
+    signal
+}
+
+fn to_channel(value: f32) -> u8 {
+    let value = if value <= 0.0 {
+        0.0
+    } else {
+        value.powf(DISPLAY_GAMMA / SOURCE_GAMMA)
+    };
+    (value * 255.95).clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+#[path = "./ntsc_test.rs"]
+mod ntsc_tests;
@@ -0,0 +1,82 @@
+use super::*;
+
+fn solid_frame(pixel: u16) -> Vec<u16> {
+    vec![pixel; WIDTH * HEIGHT]
+}
+
+fn center_rgb(filter: &mut NtscFilter, pixel: u16) -> (u8, u8, u8) {
+    let out = filter.filter_frame(&solid_frame(pixel));
+    let width = filter.output_width();
+    let offset = (HEIGHT / 2 * width + width / 2) * 3;
+    (out[offset], out[offset + 1], out[offset + 2])
+}
+
+#[test]
+fn test_output_dimensions() {
+    let mut filter = NtscFilter::new(NtscSettings {
+        output_width: 602,
+        ..NtscSettings::default()
+    });
+    let out = filter.filter_frame(&solid_frame(0x0F));
+    assert_eq!(out.len(), 602 * HEIGHT * 3);
+    assert_eq!(filter.output_height(), HEIGHT);
+}
+
+#[test]
+fn test_black_and_white_levels() {
+    let mut filter = NtscFilter::default();
+    assert_eq!(center_rgb(&mut filter, 0x0F), (0, 0, 0));
+    let (r, g, b) = center_rgb(&mut filter, 0x20);
+    assert!(r > 240 && g > 240 && b > 240);
+}
+
+#[test]
+fn test_primary_hues() {
+    let mut filter = NtscFilter::default();
+    let (r, g, b) = center_rgb(&mut filter, 0x16);
+    assert!(r > g && r > b, "red {} {} {}", r, g, b);
+    let (r, g, b) = center_rgb(&mut filter, 0x1A);
+    assert!(g > r && g > b, "green {} {} {}", r, g, b);
+    let (r, g, b) = center_rgb(&mut filter, 0x12);
+    assert!(b > r && b > g, "blue {} {} {}", r, g, b);
+}
+
+#[test]
+fn test_zero_saturation_is_grey() {
+    let mut filter = NtscFilter::new(NtscSettings {
+        saturation: 0.0,
+        ..NtscSettings::default()
+    });
+    let (r, g, b) = center_rgb(&mut filter, 0x16);
+    assert_eq!(r, g);
+    assert_eq!(g, b);
+}
+
+#[test]
+fn test_emphasis_darkens() {
+    let mut filter = NtscFilter::default();
+    let (r, g, b) = center_rgb(&mut filter, 0x30);
+    let (er, eg, eb) = center_rgb(&mut filter, 0x30 | 0b111 << 6);
+    assert!(er < r && eg < g && eb < b);
+}
+
+#[test]
+fn test_dot_crawl_changes_phase_between_frames() {
+    // a one pixel wide stripe pattern shows the fringing most clearly
+    let frame: Vec<u16> = (0..WIDTH * HEIGHT)
+        .map(|i| if i % 2 == 0 { 0x30 } else { 0x0F })
+        .collect();
+
+    let mut filter = NtscFilter::default();
+    let first = filter.filter_frame(&frame);
+    let second = filter.filter_frame(&frame);
+    assert_ne!(first, second);
+
+    let mut filter = NtscFilter::new(NtscSettings {
+        dot_crawl: false,
+        ..NtscSettings::default()
+    });
+    let first = filter.filter_frame(&frame);
+    let second = filter.filter_frame(&frame);
+    assert_eq!(first, second);
+}
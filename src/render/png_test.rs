@@ -0,0 +1,69 @@
+use super::*;
+
+// Undoes zlib_stored, checking the framing along the way.
+fn inflate_stored(stream: &[u8]) -> Vec<u8> {
+    assert_eq!(&stream[0..2], &[0x78, 0x01]);
+    assert_eq!(u16::from_be_bytes([stream[0], stream[1]]) % 31, 0);
+    let mut out = Vec::new();
+    let mut pos = 2;
+    loop {
+        let last = stream[pos] & 1 == 1;
+        assert_eq!(stream[pos] & 0b110, 0, "not a stored block");
+        let len = u16::from_le_bytes([stream[pos + 1], stream[pos + 2]]);
+        let nlen = u16::from_le_bytes([stream[pos + 3], stream[pos + 4]]);
+        assert_eq!(len, !nlen);
+        pos += 5;
+        out.extend_from_slice(&stream[pos..pos + len as usize]);
+        pos += len as usize;
+        if last {
+            break;
+        }
+    }
+    assert_eq!(&stream[pos..], &adler32(&out).to_be_bytes());
+    out
+}
+
+#[test]
+fn test_checksums() {
+    assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+}
+
+#[test]
+fn test_zlib_stored_round_trip() {
+    assert_eq!(inflate_stored(&zlib_stored(&[])), Vec::<u8>::new());
+    let data: Vec<u8> = (0..200_000).map(|n| (n * 7) as u8).collect();
+    let stream = zlib_stored(&data);
+    //four blocks of framing, the zlib header and the checksum
+    assert_eq!(stream.len(), data.len() + 4 * 5 + 6);
+    assert_eq!(inflate_stored(&stream), data);
+}
+
+#[test]
+fn test_png_structure() {
+    let rgb: Vec<u8> = (0..2 * 3 * 3).map(|n| n as u8).collect();
+    let png = encode_png(3, 2, &rgb);
+    assert_eq!(&png[0..8], &SIGNATURE);
+
+    let mut chunks = Vec::new();
+    let mut pos = 8;
+    while pos < png.len() {
+        let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+        let body = &png[pos + 4..pos + 8 + len];
+        let crc = u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+        assert_eq!(crc32(body), crc);
+        chunks.push((body[0..4].to_vec(), body[4..].to_vec()));
+        pos += 12 + len;
+    }
+    let kinds: Vec<&[u8]> = chunks.iter().map(|(kind, _)| kind.as_slice()).collect();
+    assert_eq!(kinds, vec![b"IHDR".as_slice(), b"IDAT", b"IEND"]);
+    assert_eq!(&chunks[0].1, &[0, 0, 0, 3, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+
+    let raw = inflate_stored(&chunks[1].1);
+    assert_eq!(raw.len(), 2 * (3 * 3 + 1));
+    assert_eq!(raw[0], 0);
+    assert_eq!(&raw[1..10], &rgb[0..9]);
+    assert_eq!(raw[10], 0);
+    assert_eq!(&raw[11..20], &rgb[9..18]);
+}
@@ -0,0 +1,56 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::frame::Frame;
+use super::frame::{HEIGHT, WIDTH};
+use super::png;
+
+// NTSC TVs hide roughly the top and bottom eight lines.
+const OVERSCAN_LINES: usize = 8;
+// NES pixels are slightly wider than tall on an NTSC screen.
+const PIXEL_ASPECT: (usize, usize) = (8, 7);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScreenshotOptions {
+    // stretch horizontally to 8:7 pixels
+    pub pixel_aspect: bool,
+    pub crop_overscan: bool,
+}
+
+// Returns the width, height and RGB24 pixels of the frame as it will be saved.
+pub fn prepare(frame: &Frame, options: ScreenshotOptions) -> (usize, usize, Vec<u8>) {
+    let (top, height) = if options.crop_overscan {
+        (OVERSCAN_LINES, HEIGHT - 2 * OVERSCAN_LINES)
+    } else {
+        (0, HEIGHT)
+    };
+    let width = if options.pixel_aspect {
+        WIDTH * PIXEL_ASPECT.0 / PIXEL_ASPECT.1
+    } else {
+        WIDTH
+    };
+
+    let mut rgb = Vec::with_capacity(width * height * 3);
+    for y in top..top + height {
+        for x in 0..width {
+            // nearest neighbour is enough for the 8:7 stretch
+            let (r, g, b) = frame.pixel(x * WIDTH / width, y);
+            rgb.extend_from_slice(&[r, g, b]);
+        }
+    }
+    (width, height, rgb)
+}
+
+pub fn encode(frame: &Frame, options: ScreenshotOptions) -> Vec<u8> {
+    let (width, height, rgb) = prepare(frame, options);
+    png::encode_png(width, height, &rgb)
+}
+
+pub fn save_png(frame: &Frame, path: &Path, options: ScreenshotOptions) -> io::Result<()> {
+    fs::write(path, encode(frame, options))
+}
+
+#[cfg(test)]
+#[path = "./screenshot_test.rs"]
+mod screenshot_tests;
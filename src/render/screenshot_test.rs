@@ -0,0 +1,56 @@
+use super::*;
+use crate::headless::TempDir;
+
+fn gradient_frame() -> Frame {
+    let mut frame = Frame::new();
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            frame.set_pixel(x, y, (x as u8, y as u8, 0x80));
+        }
+    }
+    frame
+}
+
+#[test]
+fn test_native_size() {
+    let (width, height, rgb) = prepare(&gradient_frame(), ScreenshotOptions::default());
+    assert_eq!((width, height), (256, 240));
+    assert_eq!(rgb, gradient_frame().pixels);
+}
+
+#[test]
+fn test_overscan_crop() {
+    let options = ScreenshotOptions {
+        crop_overscan: true,
+        ..ScreenshotOptions::default()
+    };
+    let (width, height, rgb) = prepare(&gradient_frame(), options);
+    assert_eq!((width, height), (256, 224));
+    //first row kept is line 8
+    assert_eq!(&rgb[0..3], &[0, 8, 0x80]);
+    assert_eq!(rgb[rgb.len() - 2], 231);
+}
+
+#[test]
+fn test_pixel_aspect_stretch() {
+    let options = ScreenshotOptions {
+        pixel_aspect: true,
+        crop_overscan: true,
+    };
+    let (width, height, rgb) = prepare(&gradient_frame(), options);
+    assert_eq!((width, height), (292, 224));
+    assert_eq!(rgb.len(), 292 * 224 * 3);
+    //the rightmost column still comes from the last source pixel
+    assert_eq!(rgb[(width - 1) * 3], 255);
+}
+
+#[test]
+fn test_save_png() {
+    let dir = TempDir::new("png");
+    let path = dir.join("frame.png");
+    save_png(&gradient_frame(), &path, ScreenshotOptions::default()).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    assert_eq!(&bytes[1..4], b"PNG");
+    //IHDR width and height
+    assert_eq!(&bytes[16..24], &[0, 0, 1, 0, 0, 0, 0, 240]);
+}